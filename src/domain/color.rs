@@ -0,0 +1,293 @@
+use std::fmt::Display;
+
+use phf::phf_map;
+
+/// Colour used by the pen, fills and the background. Channels are in range 0-255.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+/// Colours behind palette indices 0-15, same as in UCBLogo. SETPALETTE can change them.
+pub const DEFAULT_PALETTE: [Color; 16] = [
+    Color::rgb(0, 0, 0),       // 0 black
+    Color::rgb(0, 0, 255),     // 1 blue
+    Color::rgb(0, 255, 0),     // 2 green
+    Color::rgb(0, 255, 255),   // 3 cyan
+    Color::rgb(255, 0, 0),     // 4 red
+    Color::rgb(255, 0, 255),   // 5 magenta
+    Color::rgb(255, 255, 0),   // 6 yellow
+    Color::rgb(255, 255, 255), // 7 white
+    Color::rgb(155, 96, 59),   // 8 brown
+    Color::rgb(197, 136, 18),  // 9 tan
+    Color::rgb(100, 162, 64),  // 10 forest
+    Color::rgb(120, 187, 187), // 11 aqua
+    Color::rgb(255, 149, 119), // 12 salmon
+    Color::rgb(144, 113, 208), // 13 purple
+    Color::rgb(255, 163, 0),   // 14 orange
+    Color::rgb(183, 183, 183), // 15 grey
+];
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue, alpha: 255 }
+    }
+
+    /// Parses `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`.
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let digits = hex.strip_prefix('#')?;
+        if !digits.chars().all(|x| x.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |index: usize, width: usize| {
+            let value = u8::from_str_radix(&digits[index * width..(index + 1) * width], 16).unwrap();
+            // Short form repeats the digit, `#f80` is `#ff8800`.
+            if width == 1 { value * 17 } else { value }
+        };
+        match digits.len() {
+            3 => Some(Color::rgb(channel(0, 1), channel(1, 1), channel(2, 1))),
+            4 => Some(Color { red: channel(0, 1), green: channel(1, 1), blue: channel(2, 1), alpha: channel(3, 1) }),
+            6 => Some(Color::rgb(channel(0, 2), channel(1, 2), channel(2, 2))),
+            8 => Some(Color { red: channel(0, 2), green: channel(1, 2), blue: channel(2, 2), alpha: channel(3, 2) }),
+            _ => None,
+        }
+    }
+
+    /// CSS colour name, e.g. `red` or `cornflowerblue`.
+    pub fn from_name(name: &str) -> Option<Color> {
+        let value = *CSS_COLOR_NAMES.get(name.to_ascii_lowercase().as_str())?;
+        if name.eq_ignore_ascii_case("transparent") {
+            return Some(Color { red: 0, green: 0, blue: 0, alpha: 0 });
+        }
+        Some(Color::rgb((value >> 16) as u8, (value >> 8) as u8, value as u8))
+    }
+
+    /// Hue in degrees, saturation and value in range 0-100.
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Color {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let saturation = saturation.clamp(0.0, 100.0) / 100.0;
+        let value = value.clamp(0.0, 100.0) / 100.0;
+        let chroma = value * saturation;
+        let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (red, green, blue) = match hue as u8 {
+            0 => (chroma, second, 0.0),
+            1 => (second, chroma, 0.0),
+            2 => (0.0, chroma, second),
+            3 => (0.0, second, chroma),
+            4 => (second, 0.0, chroma),
+            _ => (chroma, 0.0, second),
+        };
+        let offset = value - chroma;
+        let channel = |x: f64| ((x + offset) * 255.0).round() as u8;
+        Color::rgb(channel(red), channel(green), channel(blue))
+    }
+
+    pub fn to_rgba(self) -> [u8; 4] {
+        [self.red, self.green, self.blue, self.alpha]
+    }
+}
+
+impl Display for Color {
+    /// Hex form that is understood by the canvas as well as by COLOR.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)?;
+        if self.alpha != 255 {
+            write!(f, "{:02x}", self.alpha)?;
+        }
+        Ok(())
+    }
+}
+
+static CSS_COLOR_NAMES: phf::Map<&'static str, u32> = phf_map! {
+    "aliceblue" => 0xf0f8ff,
+    "antiquewhite" => 0xfaebd7,
+    "aqua" => 0x00ffff,
+    "aquamarine" => 0x7fffd4,
+    "azure" => 0xf0ffff,
+    "beige" => 0xf5f5dc,
+    "bisque" => 0xffe4c4,
+    "black" => 0x000000,
+    "blanchedalmond" => 0xffebcd,
+    "blue" => 0x0000ff,
+    "blueviolet" => 0x8a2be2,
+    "brown" => 0xa52a2a,
+    "burlywood" => 0xdeb887,
+    "cadetblue" => 0x5f9ea0,
+    "chartreuse" => 0x7fff00,
+    "chocolate" => 0xd2691e,
+    "coral" => 0xff7f50,
+    "cornflowerblue" => 0x6495ed,
+    "cornsilk" => 0xfff8dc,
+    "crimson" => 0xdc143c,
+    "cyan" => 0x00ffff,
+    "darkblue" => 0x00008b,
+    "darkcyan" => 0x008b8b,
+    "darkgoldenrod" => 0xb8860b,
+    "darkgray" => 0xa9a9a9,
+    "darkgreen" => 0x006400,
+    "darkgrey" => 0xa9a9a9,
+    "darkkhaki" => 0xbdb76b,
+    "darkmagenta" => 0x8b008b,
+    "darkolivegreen" => 0x556b2f,
+    "darkorange" => 0xff8c00,
+    "darkorchid" => 0x9932cc,
+    "darkred" => 0x8b0000,
+    "darksalmon" => 0xe9967a,
+    "darkseagreen" => 0x8fbc8f,
+    "darkslateblue" => 0x483d8b,
+    "darkslategray" => 0x2f4f4f,
+    "darkslategrey" => 0x2f4f4f,
+    "darkturquoise" => 0x00ced1,
+    "darkviolet" => 0x9400d3,
+    "deeppink" => 0xff1493,
+    "deepskyblue" => 0x00bfff,
+    "dimgray" => 0x696969,
+    "dimgrey" => 0x696969,
+    "dodgerblue" => 0x1e90ff,
+    "firebrick" => 0xb22222,
+    "floralwhite" => 0xfffaf0,
+    "forestgreen" => 0x228b22,
+    "fuchsia" => 0xff00ff,
+    "gainsboro" => 0xdcdcdc,
+    "ghostwhite" => 0xf8f8ff,
+    "gold" => 0xffd700,
+    "goldenrod" => 0xdaa520,
+    "gray" => 0x808080,
+    "green" => 0x008000,
+    "greenyellow" => 0xadff2f,
+    "grey" => 0x808080,
+    "honeydew" => 0xf0fff0,
+    "hotpink" => 0xff69b4,
+    "indianred" => 0xcd5c5c,
+    "indigo" => 0x4b0082,
+    "ivory" => 0xfffff0,
+    "khaki" => 0xf0e68c,
+    "lavender" => 0xe6e6fa,
+    "lavenderblush" => 0xfff0f5,
+    "lawngreen" => 0x7cfc00,
+    "lemonchiffon" => 0xfffacd,
+    "lightblue" => 0xadd8e6,
+    "lightcoral" => 0xf08080,
+    "lightcyan" => 0xe0ffff,
+    "lightgoldenrodyellow" => 0xfafad2,
+    "lightgray" => 0xd3d3d3,
+    "lightgreen" => 0x90ee90,
+    "lightgrey" => 0xd3d3d3,
+    "lightpink" => 0xffb6c1,
+    "lightsalmon" => 0xffa07a,
+    "lightseagreen" => 0x20b2aa,
+    "lightskyblue" => 0x87cefa,
+    "lightslategray" => 0x778899,
+    "lightslategrey" => 0x778899,
+    "lightsteelblue" => 0xb0c4de,
+    "lightyellow" => 0xffffe0,
+    "lime" => 0x00ff00,
+    "limegreen" => 0x32cd32,
+    "linen" => 0xfaf0e6,
+    "magenta" => 0xff00ff,
+    "maroon" => 0x800000,
+    "mediumaquamarine" => 0x66cdaa,
+    "mediumblue" => 0x0000cd,
+    "mediumorchid" => 0xba55d3,
+    "mediumpurple" => 0x9370db,
+    "mediumseagreen" => 0x3cb371,
+    "mediumslateblue" => 0x7b68ee,
+    "mediumspringgreen" => 0x00fa9a,
+    "mediumturquoise" => 0x48d1cc,
+    "mediumvioletred" => 0xc71585,
+    "midnightblue" => 0x191970,
+    "mintcream" => 0xf5fffa,
+    "mistyrose" => 0xffe4e1,
+    "moccasin" => 0xffe4b5,
+    "navajowhite" => 0xffdead,
+    "navy" => 0x000080,
+    "oldlace" => 0xfdf5e6,
+    "olive" => 0x808000,
+    "olivedrab" => 0x6b8e23,
+    "orange" => 0xffa500,
+    "orangered" => 0xff4500,
+    "orchid" => 0xda70d6,
+    "palegoldenrod" => 0xeee8aa,
+    "palegreen" => 0x98fb98,
+    "paleturquoise" => 0xafeeee,
+    "palevioletred" => 0xdb7093,
+    "papayawhip" => 0xffefd5,
+    "peachpuff" => 0xffdab9,
+    "peru" => 0xcd853f,
+    "pink" => 0xffc0cb,
+    "plum" => 0xdda0dd,
+    "powderblue" => 0xb0e0e6,
+    "purple" => 0x800080,
+    "rebeccapurple" => 0x663399,
+    "red" => 0xff0000,
+    "rosybrown" => 0xbc8f8f,
+    "royalblue" => 0x4169e1,
+    "saddlebrown" => 0x8b4513,
+    "salmon" => 0xfa8072,
+    "sandybrown" => 0xf4a460,
+    "seagreen" => 0x2e8b57,
+    "seashell" => 0xfff5ee,
+    "sienna" => 0xa0522d,
+    "silver" => 0xc0c0c0,
+    "skyblue" => 0x87ceeb,
+    "slateblue" => 0x6a5acd,
+    "slategray" => 0x708090,
+    "slategrey" => 0x708090,
+    "snow" => 0xfffafa,
+    "springgreen" => 0x00ff7f,
+    "steelblue" => 0x4682b4,
+    "tan" => 0xd2b48c,
+    "teal" => 0x008080,
+    "thistle" => 0xd8bfd8,
+    "tomato" => 0xff6347,
+    "transparent" => 0x000000,
+    "turquoise" => 0x40e0d0,
+    "violet" => 0xee82ee,
+    "wheat" => 0xf5deb3,
+    "white" => 0xffffff,
+    "whitesmoke" => 0xf5f5f5,
+    "yellow" => 0xffff00,
+    "yellowgreen" => 0x9acd32,
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::color::Color;
+
+    #[test]
+    fn parsing_hex_colors() {
+        assert_eq!(Color::from_hex("#ff8000"), Some(Color::rgb(255, 128, 0)));
+        assert_eq!(Color::from_hex("#f80"), Some(Color::rgb(255, 136, 0)));
+        assert_eq!(Color::from_hex("#f808"), Some(Color { red: 255, green: 136, blue: 0, alpha: 136 }));
+        assert_eq!(Color::from_hex("#FF800080"), Some(Color { red: 255, green: 128, blue: 0, alpha: 128 }));
+        assert_eq!(Color::from_hex("#ff800"), None);
+        assert_eq!(Color::from_hex("#gg0000"), None);
+    }
+
+    #[test]
+    fn parsing_color_names() {
+        assert_eq!(Color::from_name("Red"), Some(Color::rgb(255, 0, 0)));
+        assert_eq!(Color::from_name("cornflowerblue"), Some(Color::rgb(100, 149, 237)));
+        assert_eq!(Color::from_name("transparent").map(|x| x.alpha), Some(0));
+        assert_eq!(Color::from_name("reddish"), None);
+    }
+
+    #[test]
+    fn converting_hsv() {
+        assert_eq!(Color::from_hsv(0.0, 100.0, 100.0), Color::rgb(255, 0, 0));
+        assert_eq!(Color::from_hsv(120.0, 100.0, 50.0), Color::rgb(0, 128, 0));
+        assert_eq!(Color::from_hsv(240.0, 50.0, 100.0), Color::rgb(128, 128, 255));
+        assert_eq!(Color::from_hsv(360.0, 0.0, 100.0), Color::rgb(255, 255, 255));
+    }
+
+    #[test]
+    fn displaying_as_hex() {
+        assert_eq!(Color::rgb(255, 136, 0).to_string(), "#ff8800");
+        assert_eq!(Color { red: 0, green: 0, blue: 0, alpha: 128 }.to_string(), "#00000080");
+    }
+}
@@ -1,5 +1,23 @@
 pub const CANVAS_ID: &'static str = "canvas";
 
+// Loops stop with an error after this many iterations
+// so a mistake in the code does not freeze the browser tab.
+pub const MAX_LOOP_ITERATIONS: usize = 100_000;
+
+// Default limit for nested procedure calls. Scripts can change it with SETRECURSIONDEPTH.
+pub const MAX_RECURSION_DEPTH: usize = 256;
+
+// Highest limit SETRECURSIONDEPTH accepts. Deeper recursion could overflow the WASM stack.
+pub const RECURSION_DEPTH_CEILING: usize = 512;
+
+// Stack size of the WASM build, set by the linker flag in `.cargo/config.toml`.
+// Tests run recursion up to RECURSION_DEPTH_CEILING on a stack of this size.
+#[cfg(test)]
+pub const WASM_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+// Seed used by RANDOM until the script calls RERANDOM.
+pub const DEFAULT_RANDOM_SEED: u64 = 0;
+
 pub const INITIAL_CODE: &'static str =
     r#"REPEAT 3 [
     COLOR #00ff00
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum InterpreterError {
     Tokenize {
         line: usize,
@@ -10,6 +10,9 @@ pub enum InterpreterError {
         line: usize,
         message: String
     },
+    Runtime {
+        message: String,
+    },
 }
 
 impl Display for InterpreterError {
@@ -17,6 +20,7 @@ impl Display for InterpreterError {
         match self {
             InterpreterError::Tokenize { line, message } => write!(f, "Tokenizer Error at line {}: {}", line, message),
             InterpreterError::Parser { line, message } => write!(f, "Parser Error at line{}: {}", line, message),
+            InterpreterError::Runtime { message } => write!(f, "Runtime Error: {}", message),
         }
     }
 }
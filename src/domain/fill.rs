@@ -0,0 +1,118 @@
+// Flood fill over raw canvas pixels. Kept separate from the canvas code so it can be tested natively.
+
+/// Pixels that differ from the starting pixel by at most this much in every channel are filled too.
+/// Without it, anti-aliased edges of lines would be left as a fringe around the fill.
+const TOLERANCE: u8 = 48;
+
+/// Fills the area connected to `(x, y)` that has the same color as the pixel at `(x, y)`.
+/// `pixels` are RGBA bytes, row by row, as returned by the canvas `getImageData`.
+pub fn flood_fill(pixels: &mut [u8], width: usize, height: usize, x: usize, y: usize, color: [u8; 4]) {
+    if x >= width || y >= height {
+        return;
+    }
+    let target = pixel_at(pixels, y * width + x);
+    let mut visited = vec![false; width * height];
+    let mut stack = vec![(x, y)];
+    while let Some((x, y)) = stack.pop() {
+        let index = y * width + x;
+        if visited[index] || !is_similar(&pixel_at(pixels, index), &target) {
+            continue;
+        }
+        visited[index] = true;
+        pixels[index * 4..index * 4 + 4].copy_from_slice(&color);
+        if x > 0 {
+            stack.push((x - 1, y));
+        }
+        if x + 1 < width {
+            stack.push((x + 1, y));
+        }
+        if y > 0 {
+            stack.push((x, y - 1));
+        }
+        if y + 1 < height {
+            stack.push((x, y + 1));
+        }
+    }
+}
+
+/// Puts `color` under the drawing in place of the `previous` background.
+/// Each pixel is read as the drawing blended over `previous` with the least opacity that gives its color,
+/// so anti-aliased edges are blended over the new background instead of keeping a halo of the old one.
+pub fn replace_background(pixels: &mut [u8], previous: [u8; 4], color: [u8; 4]) {
+    for pixel in pixels.chunks_mut(4) {
+        let transparency = 1.0 - coverage(pixel, &previous);
+        for channel in 0..4 {
+            let change = transparency * (color[channel] as f64 - previous[channel] as f64);
+            pixel[channel] = (pixel[channel] as f64 + change).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+fn coverage(pixel: &[u8], background: &[u8; 4]) -> f64 {
+    // Least opacity of a drawing over `background` that results in the color of `pixel`.
+    (0..3)
+        .map(|channel| {
+            let (value, background) = (pixel[channel] as f64, background[channel] as f64);
+            if value > background {
+                (value - background) / (255.0 - background)
+            } else if value < background {
+                (background - value) / background
+            } else {
+                0.0
+            }
+        })
+        .fold(0.0, f64::max)
+}
+
+fn pixel_at(pixels: &[u8], index: usize) -> [u8; 4] {
+    [pixels[index * 4], pixels[index * 4 + 1], pixels[index * 4 + 2], pixels[index * 4 + 3]]
+}
+
+fn is_similar(first: &[u8; 4], second: &[u8; 4]) -> bool {
+    first.iter().zip(second.iter()).all(|(x, y)| x.abs_diff(*y) <= TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::fill::{flood_fill, replace_background};
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const LINE: [u8; 4] = [0, 0, 0, 255];
+    const EMPTY: [u8; 4] = [0, 0, 0, 0];
+
+    #[test]
+    fn fill_stops_at_lines() {
+        // 4x3 canvas with a vertical line in the third column.
+        let mut pixels: Vec<u8> = (0..12)
+            .flat_map(|index| if index % 4 == 2 { LINE } else { EMPTY })
+            .collect();
+        flood_fill(&mut pixels, 4, 3, 0, 1, RED);
+        let colors: Vec<&[u8]> = pixels.chunks(4).collect();
+        assert_eq!(colors[0], RED);
+        assert_eq!(colors[9], RED);
+        assert_eq!(colors[2], LINE);
+        assert_eq!(colors[3], EMPTY);
+    }
+
+    #[test]
+    fn replacing_background_keeps_lines() {
+        let mut pixels: Vec<u8> = [RED, LINE, RED].into_iter().flatten().collect();
+        replace_background(&mut pixels, RED, EMPTY);
+        assert_eq!(pixels, [EMPTY, LINE, EMPTY].into_iter().flatten().collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn replacing_background_blends_line_edges() {
+        // Edge of a black line, half covering a white background.
+        let mut pixels = vec![128, 128, 128, 255];
+        replace_background(&mut pixels, [255, 255, 255, 255], RED);
+        assert_eq!(pixels, vec![128, 0, 0, 255]);
+    }
+
+    #[test]
+    fn fill_with_same_color_terminates() {
+        let mut pixels: Vec<u8> = (0..4).flat_map(|_| RED).collect();
+        flood_fill(&mut pixels, 2, 2, 1, 1, RED);
+        assert!(pixels.chunks(4).all(|x| x == RED));
+    }
+}
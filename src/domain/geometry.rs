@@ -1,7 +1,7 @@
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub struct Location2d {
-    pub x: f32,
-    pub y: f32,
+    pub x: f64,
+    pub y: f64,
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -10,3 +10,168 @@ pub struct Size2d {
     pub width: f64,
     pub height: f64
 }
+
+/// Part of a path recorded by FILLED, in canvas coordinates.
+#[derive(PartialEq, Clone, Debug)]
+pub enum PathSegment {
+    MoveTo(Location2d),
+    LineTo(Location2d),
+    /// Angles are in radians clockwise from east, same as the canvas `arc` function.
+    Arc { centre: Location2d, radius: f64, start_angle: f64, end_angle: f64, anticlockwise: bool },
+}
+
+/// What happens when the turtle moves past the edge of the canvas.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum BoundaryMode {
+    /// Turtle keeps going off-screen.
+    Window,
+    /// Turtle comes back from the opposite edge.
+    Wrap,
+    /// Moving past the edge is an error.
+    Fence,
+}
+
+/// Maps Logo coordinates to canvas pixels.
+/// Logo has the origin in the centre, y pointing up and headings in degrees clockwise from north.
+/// Canvas has the origin in the top-left corner, y pointing down and angles in radians clockwise from east.
+#[derive(PartialEq, Clone, Debug)]
+pub struct CanvasTransform {
+    pub canvas_size: Size2d,
+}
+
+impl CanvasTransform {
+    pub fn new(canvas_size: Size2d) -> CanvasTransform {
+        CanvasTransform { canvas_size }
+    }
+
+    pub fn to_canvas(&self, location: &Location2d) -> Location2d {
+        Location2d {
+            x: self.canvas_size.width / 2.0 + location.x,
+            y: self.canvas_size.height / 2.0 - location.y,
+        }
+    }
+
+    pub fn to_canvas_angle(&self, heading: f64) -> f64 {
+        (heading - 90.0).to_radians()
+    }
+
+    /// Whether a location in Logo coordinates is on the canvas. Edges count as inside.
+    pub fn contains(&self, location: &Location2d) -> bool {
+        location.x.abs() <= self.canvas_size.width / 2.0 && location.y.abs() <= self.canvas_size.height / 2.0
+    }
+
+    /// Splits a line in Logo coordinates into the pieces drawn in WRAP mode.
+    /// Each time the line leaves through an edge, it continues from the opposite edge.
+    /// A line starting off the canvas is first moved onto it. Last piece ends where the turtle ends.
+    pub fn wrap_line(&self, from: &Location2d, to: &Location2d) -> Vec<(Location2d, Location2d)> {
+        let half_width = self.canvas_size.width / 2.0;
+        let half_height = self.canvas_size.height / 2.0;
+        let wrap = |value: f64, half: f64| (value + half).rem_euclid(2.0 * half) - half;
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        // Skip all but the last full turn around the canvas, so a huge move is
+        // traced in a few segments. Along an axis the skipped turns retrace the same line.
+        let turns = (dx.abs() / (2.0 * half_width)).max(dy.abs() / (2.0 * half_height));
+        let skipped = if turns >= 2.0 { (turns.floor() - 1.0) / turns } else { 0.0 };
+        let from = Location2d { x: from.x + dx * skipped, y: from.y + dy * skipped };
+        let mut start = Location2d { x: wrap(from.x, half_width), y: wrap(from.y, half_height) };
+        let mut end = Location2d { x: to.x + start.x - from.x, y: to.y + start.y - from.y };
+        let mut lines = Vec::new();
+        loop {
+            let dx = end.x - start.x;
+            let dy = end.y - start.y;
+            let exit_x = edge_fraction(start.x, dx, half_width);
+            let exit_y = edge_fraction(start.y, dy, half_height);
+            let exit = exit_x.min(exit_y);
+            if exit >= 1.0 {
+                lines.push((start, end));
+                return lines;
+            }
+            let edge = Location2d { x: start.x + dx * exit, y: start.y + dy * exit };
+            if exit > 0.0 {
+                lines.push((start, edge));
+            }
+            // Both shifts apply when the line leaves through a corner.
+            let shift_x = if exit_x == exit { -dx.signum() * 2.0 * half_width } else { 0.0 };
+            let shift_y = if exit_y == exit { -dy.signum() * 2.0 * half_height } else { 0.0 };
+            start = Location2d { x: edge.x + shift_x, y: edge.y + shift_y };
+            end = Location2d { x: end.x + shift_x, y: end.y + shift_y };
+        }
+    }
+}
+
+/// Fraction of a move along one axis after which it reaches the edge at -half or half.
+fn edge_fraction(position: f64, delta: f64, half: f64) -> f64 {
+    if delta > 0.0 {
+        (half - position) / delta
+    } else if delta < 0.0 {
+        (-half - position) / delta
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Sine and cosine of an angle in degrees. Multiples of 90 are exact,
+/// so `RT 90 FD 100` ends at y = 0 and not 6.123233995736766e-15.
+pub fn sin_cos_degrees(degrees: f64) -> (f64, f64) {
+    let degrees = degrees.rem_euclid(360.0);
+    if degrees == 0.0 {
+        (0.0, 1.0)
+    } else if degrees == 90.0 {
+        (1.0, 0.0)
+    } else if degrees == 180.0 {
+        (0.0, -1.0)
+    } else if degrees == 270.0 {
+        (-1.0, 0.0)
+    } else {
+        degrees.to_radians().sin_cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::geometry::{CanvasTransform, Location2d, Size2d};
+
+    #[test]
+    fn logo_origin_is_canvas_centre() {
+        let transform = CanvasTransform::new(Size2d { width: 300.0, height: 200.0 });
+        assert_eq!(transform.to_canvas(&Location2d { x: 0.0, y: 0.0 }), Location2d { x: 150.0, y: 100.0 });
+        assert_eq!(transform.to_canvas(&Location2d { x: 10.0, y: 20.0 }), Location2d { x: 160.0, y: 80.0 });
+        // North points up on the canvas, which is -90 degrees from the canvas x axis.
+        assert_eq!(transform.to_canvas_angle(0.0), -std::f64::consts::FRAC_PI_2);
+    }
+
+    #[test]
+    fn wrapped_line_is_split_at_the_edges() {
+        let transform = CanvasTransform::new(Size2d { width: 200.0, height: 100.0 });
+        let at = |x: f64, y: f64| Location2d { x, y };
+        assert_eq!(transform.wrap_line(&at(0.0, 0.0), &at(50.0, 0.0)), vec![(at(0.0, 0.0), at(50.0, 0.0))]);
+        assert_eq!(
+            transform.wrap_line(&at(50.0, 0.0), &at(350.0, 0.0)),
+            vec![
+                (at(50.0, 0.0), at(100.0, 0.0)),
+                (at(-100.0, 0.0), at(100.0, 0.0)),
+                (at(-100.0, 0.0), at(-50.0, 0.0)),
+            ]
+        );
+        // Leaving through the top-right corner comes back at the bottom-left one.
+        assert_eq!(
+            transform.wrap_line(&at(90.0, 40.0), &at(110.0, 60.0)),
+            vec![(at(90.0, 40.0), at(100.0, 50.0)), (at(-100.0, -50.0), at(-90.0, -40.0))]
+        );
+        // Line starting off the canvas is moved onto it first.
+        assert_eq!(transform.wrap_line(&at(250.0, 0.0), &at(260.0, 0.0)), vec![(at(50.0, 0.0), at(60.0, 0.0))]);
+    }
+
+    #[test]
+    fn huge_wrapped_line_is_traced_in_a_few_segments() {
+        let transform = CanvasTransform::new(Size2d { width: 200.0, height: 100.0 });
+        let at = |x, y| Location2d { x, y };
+        let lines = transform.wrap_line(&at(0.0, 0.0), &at(1e14 + 50.0, 0.0));
+        assert!(lines.len() <= 3);
+        let end = lines.last().unwrap().1;
+        assert!((end.x - 50.0).abs() < 1.0 && end.y == 0.0);
+        let lines = transform.wrap_line(&at(0.0, 0.0), &at(3e13, 4e13));
+        assert!(lines.len() <= 7);
+        assert!(lines.iter().all(|(start, end)| transform.contains(start) && transform.contains(end)));
+    }
+}
@@ -0,0 +1,173 @@
+use wasm_bindgen::{Clamped, JsCast, JsValue};
+use web_sys::CanvasRenderingContext2d;
+
+use crate::domain::config::CANVAS_ID;
+use crate::domain::color::Color;
+use crate::domain::fill::{flood_fill, replace_background};
+use crate::domain::geometry::{Location2d, PathSegment};
+use crate::domain::turtle::{PenMode, Turtle};
+
+// Drawing on the HTML canvas. Everything here needs the DOM, so it can't run in native tests.
+// Drawing functions take `on_background`, which is true once SETBACKGROUND made the canvas opaque.
+
+pub fn get_context() -> CanvasRenderingContext2d {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas = document.get_element_by_id(CANVAS_ID).unwrap();
+    let canvas: web_sys::HtmlCanvasElement =
+        canvas
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .map_err(|_| ())
+            .unwrap();
+
+    canvas
+        .get_context("2d")
+        .unwrap()
+        .unwrap()
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .unwrap()
+}
+
+pub fn draw_line(turtle: &Turtle, on_background: bool, from: &Location2d, to: &Location2d) {
+    let context = get_context();
+    context.save();
+    apply_pen(&context, turtle, on_background);
+    context.begin_path();
+    context.move_to(from.x, from.y);
+    context.line_to(to.x, to.y);
+    context.stroke();
+    context.restore();
+}
+
+/// Angles are in radians clockwise from east, same as the canvas `arc` function.
+pub fn draw_arc(turtle: &Turtle, on_background: bool, centre: &Location2d, radius: f64, start_angle: f64, end_angle: f64, anticlockwise: bool) {
+    let context = get_context();
+    context.save();
+    apply_pen(&context, turtle, on_background);
+    context.begin_path();
+    let _ = context.arc_with_anticlockwise(centre.x, centre.y, radius, start_angle, end_angle, anticlockwise);
+    context.stroke();
+    context.restore();
+}
+
+/// Draws text with its baseline starting at `at`, rotated by `angle` radians clockwise.
+pub fn draw_label(turtle: &Turtle, on_background: bool, text: &str, at: &Location2d, angle: f64) {
+    let context = get_context();
+    context.save();
+    let (operation, color) = pen_style(turtle, on_background);
+    let _ = context.set_global_composite_operation(operation);
+    context.set_fill_style(&JsValue::from_str(&color.to_string()));
+    context.set_font(&format!("{}px {}", turtle.label_height, turtle.font));
+    let _ = context.translate(at.x, at.y);
+    let _ = context.rotate(angle);
+    let _ = context.fill_text(text, 0.0, 0.0);
+    context.restore();
+}
+
+/// Draws the turtle shape in the pen color, on `at` and turned towards the turtle heading.
+pub fn draw_turtle(turtle: &Turtle, at: &Location2d) {
+    let path = match web_sys::Path2d::new_with_path_string(turtle.shape.path_data()) {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+    let origin = turtle.shape.origin();
+    let context = get_context();
+    context.save();
+    context.set_fill_style(&JsValue::from_str(&turtle.color.to_string()));
+    let _ = context.translate(at.x, at.y);
+    // Shapes face up, which is heading 0. Canvas rotation is clockwise, same as headings.
+    let _ = context.rotate(turtle.heading.to_radians());
+    let _ = context.translate(-origin.x, -origin.y);
+    context.fill_with_path_2d(&path);
+    context.restore();
+}
+
+pub fn fill_path(color: &Color, path: &[PathSegment]) {
+    let context = get_context();
+    context.save();
+    context.set_fill_style(&JsValue::from_str(&color.to_string()));
+    context.begin_path();
+    for segment in path {
+        match segment {
+            PathSegment::MoveTo(location) => context.move_to(location.x, location.y),
+            PathSegment::LineTo(location) => context.line_to(location.x, location.y),
+            PathSegment::Arc { centre, radius, start_angle, end_angle, anticlockwise } => {
+                let _ = context.arc_with_anticlockwise(centre.x, centre.y, *radius, *start_angle, *end_angle, *anticlockwise);
+            },
+        }
+    }
+    context.close_path();
+    context.fill();
+    context.restore();
+}
+
+/// Fills the area around `at` with the pen color.
+pub fn flood_fill_at(turtle: &Turtle, at: &Location2d) {
+    // Casting would turn negative and NaN coordinates into 0, which is a pixel on the canvas.
+    if !(at.x.is_finite() && at.y.is_finite() && at.x >= 0.0 && at.y >= 0.0) {
+        return;
+    }
+    let color = turtle.color.to_rgba();
+    update_pixels(|pixels, width, height| {
+        flood_fill(pixels, width, height, at.x.round() as usize, at.y.round() as usize, color)
+    });
+}
+
+/// Replaces the previous background with the new one and paints it behind the drawing.
+/// Background is part of the canvas pixels, so it is kept when the image is saved.
+pub fn set_background(previous: Option<Color>, color: &Color) {
+    if let Some(previous) = previous {
+        update_pixels(|pixels, _, _| replace_background(pixels, previous.to_rgba(), color.to_rgba()));
+    }
+    paint_background(color);
+}
+
+/// Paints transparent parts of the canvas, e.g. after clearing or erasing, with the background.
+pub fn paint_background(color: &Color) {
+    let context = get_context();
+    let canvas = context.canvas().unwrap();
+    context.save();
+    let _ = context.set_global_composite_operation("destination-over");
+    context.set_fill_style(&JsValue::from_str(&color.to_string()));
+    context.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+    context.restore();
+}
+
+fn update_pixels<F>(update: F)
+    where
+        F: FnOnce(&mut [u8], usize, usize),
+{
+    let context = get_context();
+    let canvas = context.canvas().unwrap();
+    let (width, height) = (canvas.width(), canvas.height());
+    let image = context.get_image_data(0.0, 0.0, width as f64, height as f64).unwrap();
+    let mut pixels = image.data().0;
+    update(&mut pixels, width as usize, height as usize);
+    let image = web_sys::ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pixels), width, height).unwrap();
+    let _ = context.put_image_data(&image, 0.0, 0.0);
+}
+
+fn apply_pen(context: &CanvasRenderingContext2d, turtle: &Turtle, on_background: bool) {
+    let (operation, color) = pen_style(turtle, on_background);
+    let _ = context.set_global_composite_operation(operation);
+    context.set_stroke_style(
+        &JsValue::from_str(&color.to_string())
+    );
+    context.set_line_width(turtle.pen_size);
+    context.set_line_cap(turtle.line_cap.as_str());
+    context.set_line_join(turtle.line_join.as_str());
+    let dash = turtle.dash.iter().map(|x| JsValue::from_f64(*x)).collect::<js_sys::Array>();
+    let _ = context.set_line_dash(&dash);
+}
+
+/// Composite operation and color the pen draws with.
+fn pen_style(turtle: &Turtle, on_background: bool) -> (&'static str, Color) {
+    match turtle.pen_mode {
+        PenMode::Paint => ("source-over", turtle.color),
+        // Only the alpha of the line matters, pixels under it become transparent.
+        PenMode::Erase => ("destination-out", turtle.color),
+        // Difference with white inverts the pixels, and inverting twice gives them back.
+        PenMode::Reverse if on_background => ("difference", Color::rgb(255, 255, 255)),
+        // Transparent pixels can't be inverted. Instead, the pen color and transparent swap places.
+        PenMode::Reverse => ("xor", turtle.color),
+    }
+}
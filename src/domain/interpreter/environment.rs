@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use crate::domain::interpreter::interpreter::Obj;
+
+/// Holds variables visible to the running code.
+/// The first frame holds global variables and every procedure call pushes a new frame with its parameters.
+/// Lookup goes from the innermost frame outwards, so Logo procedures see variables of their callers (dynamic scope).
+pub struct Environment {
+    frames: Vec<HashMap<String, Obj>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Environment {
+            frames: vec![HashMap::new()],
+        }
+    }
+
+    pub fn push_frame(&mut self, frame: HashMap<String, Obj>) {
+        self.frames.push(frame);
+    }
+
+    pub fn pop_frame(&mut self) {
+        // Global frame must never be removed.
+        if self.frames.len() > 1 {
+            self.frames.pop();
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Obj> {
+        self.frames
+            .iter()
+            .rev()
+            .find_map(|frame| frame.get(name))
+    }
+
+    pub fn set(&mut self, name: &str, value: Obj) {
+        // Like in Logo, assignment updates the innermost existing variable or creates a global one.
+        let frame =
+            self.frames
+                .iter_mut()
+                .rev()
+                .find(|frame| frame.contains_key(name));
+        match frame {
+            Some(frame) => frame.insert(name.to_string(), value),
+            None => self.frames[0].insert(name.to_string(), value),
+        };
+    }
+}
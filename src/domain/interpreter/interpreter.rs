@@ -1,38 +1,127 @@
-use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::rc::Rc;
 
-use wasm_bindgen::{JsCast, JsValue};
-
-use crate::domain::config::CANVAS_ID;
+use crate::domain::color::{Color, DEFAULT_PALETTE};
+use crate::domain::config::{DEFAULT_RANDOM_SEED, MAX_LOOP_ITERATIONS, MAX_RECURSION_DEPTH, RECURSION_DEPTH_CEILING};
 use crate::domain::errors::InterpreterError;
+use crate::domain::geometry::{BoundaryMode, CanvasTransform, Location2d, PathSegment, Size2d};
+use crate::domain::interpreter::canvas;
+use crate::domain::interpreter::environment::Environment;
+use crate::domain::interpreter::primitives;
 use crate::domain::interpreter::visitor::{ExprVisitable, ExprVisitor, StmtVisitable, StmtVisitor};
-use crate::domain::parser::expressions::{Expr, Literal};
-use crate::domain::parser::statements::{MoveBackwardStmt, MoveForwardStmt, RepeatStmt, RotateLeftStmt, RotateRightStmt, SetColorStmt, Stmt};
-use crate::domain::turtle::Turtle;
+use crate::domain::parser::builtins::Builtin;
+use crate::domain::parser::expressions::{BinaryExpr, BuiltinCallExpr, BinaryOperator, Expr, Literal, LogicalExpr, LogicalOperator, ProcedureCallExpr, UnaryExpr, UnaryOperator, VariableExpr};
+use crate::domain::parser::statements::{ArcStmt, AskStmt, FilledStmt, ForeverStmt, ForStmt, IfStmt, LabelStmt, MakeStmt, MoveBackwardStmt, MoveForwardStmt, OutputStmt, PrintStmt, PrintStyle, ProcedureCallStmt, ProcedureDeclarationStmt, RepeatStmt, RerandomStmt, RotateLeftStmt, RotateRightStmt, SetBackgroundStmt, SetColorStmt, SetDashStmt, SetPaletteStmt, SetFontStmt, SetHeadingStmt, SetLabelHeightStmt, SetPositionStmt, SetXYStmt, SetLineCapStmt, SetLineJoinStmt, SetPenSizeStmt, SetRecursionDepthStmt, SetShapeStmt, Stmt, TellStmt, WhileStmt};
+use crate::domain::random::Random;
+use crate::domain::turtle::{LineCap, LineJoin, PenMode, Turtle, TurtleShape};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Obj {
     /// Represents types that can be used for return value.
     /// They are can be a result of an expression.
     Num(f64),
-    Color(String),
+    Color(Color),
+    Bool(bool),
+    Word(String),
+    List(Vec<Obj>),
+}
+
+impl Display for Obj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Obj::Num(number) => write!(f, "{}", number),
+            Obj::Color(color) => write!(f, "{}", color),
+            Obj::Bool(value) => write!(f, "{}", value),
+            Obj::Word(word) => write!(f, "{}", word),
+            Obj::List(items) => write!(
+                f,
+                "[{}]",
+                items.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(" ")
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ProcedureExit {
+    /// Set by STOP. Procedure ends without a value.
+    Stop,
+    /// Set by OUTPUT. Procedure ends and outputs the value to its caller.
+    Output(Obj),
 }
 
+// Turtle passed to `Interpreter::new` is turtle 0.
+const FIRST_TURTLE: &str = "0";
+
 pub struct Interpreter {
-    pub return_value: Option<Obj>,
+    pub return_value: Option<ProcedureExit>,
+    pub max_recursion_depth: usize,
+    // Turtle that commands and queries are applied to. Other turtles wait in `turtles`.
     turtle: Turtle,
+    current_turtle: String,
+    turtles: BTreeMap<String, Turtle>,
+    // Turtles saved by PUSHTURTLE, by turtle id. Last saved is last.
+    saved_turtles: HashMap<String, Vec<Turtle>>,
+    // Turtles picked by TELL or ASK. Turtle commands are applied to each of them in turn.
+    active_turtles: Vec<String>,
+    transform: CanvasTransform,
+    environment: Environment,
+    procedures: HashMap<String, Rc<ProcedureDeclarationStmt>>,
+    // Current iteration of each running REPEAT or FOREVER loop, innermost last. Reported by REPCOUNT.
+    repeat_counts: Vec<usize>,
+    // Number of procedure calls currently running.
+    call_depth: usize,
+    random: Random,
+    // Text written by PRINT, SHOW and TYPE.
+    output: String,
+    // Paths traced inside running FILLED blocks, innermost last. Each turtle traces its own path.
+    filled_paths: Vec<BTreeMap<String, Vec<PathSegment>>>,
+    // Colours behind the palette indices, e.g. `COLOR 4`.
+    palette: [Color; 16],
+    // Set by WRAP, FENCE and WINDOW.
+    boundary_mode: BoundaryMode,
+    // Set by SETBACKGROUND. Canvas is transparent until then.
+    background: Option<Color>,
 }
 
 impl Interpreter {
     pub fn new(
-        turtle: Turtle
+        turtle: Turtle,
+        canvas_size: Size2d,
     ) -> Self {
         Interpreter {
             turtle,
+            current_turtle: FIRST_TURTLE.into(),
+            turtles: BTreeMap::new(),
+            active_turtles: vec![FIRST_TURTLE.into()],
+            saved_turtles: HashMap::new(),
+            transform: CanvasTransform::new(canvas_size),
             return_value: None,
+            max_recursion_depth: MAX_RECURSION_DEPTH,
+            environment: Environment::new(),
+            procedures: HashMap::new(),
+            repeat_counts: Vec::new(),
+            call_depth: 0,
+            random: Random::new(DEFAULT_RANDOM_SEED),
+            output: String::new(),
+            filled_paths: Vec::new(),
+            palette: DEFAULT_PALETTE,
+            background: None,
+            boundary_mode: BoundaryMode::Window,
         }
     }
 
-    pub fn interpret_statements(&mut self, statements: &mut Vec<Stmt>) -> Result<(), InterpreterError> {
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Seeds the generator used by RANDOM. Scripts can do the same with RERANDOM.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random = Random::new(seed);
+    }
+
+    pub fn interpret_statements(&mut self, statements: &[Stmt]) -> Result<(), InterpreterError> {
         for statement in statements {
             if self.return_value != None {
                 return Ok(());
@@ -43,167 +132,946 @@ impl Interpreter {
     }
 
     fn clear_canvas(&mut self) {
-        let document = web_sys::window().unwrap().document().unwrap();
-        let canvas = document.get_element_by_id(CANVAS_ID).unwrap();
-        let canvas: web_sys::HtmlCanvasElement =
-            canvas
-                .dyn_into::<web_sys::HtmlCanvasElement>()
-                .map_err(|_| ())
-                .unwrap();
-
-        let context = canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<web_sys::CanvasRenderingContext2d>()
-            .unwrap();
-        // TODO: Refactor canvas width and height.
-        context.clear_rect(0.0, 0.0, 365.0, 365.0);
-    }
-    fn render_turtle(&mut self){
-        let document = web_sys::window().unwrap().document().unwrap();
-        let canvas = document.get_element_by_id(CANVAS_ID).unwrap();
-        let path = web_sys::Path2d::new_with_path_string("M42.706,63.979C41.467,64.827 39.973,64.849 39.22,63.974C38.393,63.011 38.773,61.324 40.07,60.209C40.177,60.117 40.286,60.032 40.398,59.954C38.743,55.63 38.934,51.169 40.952,46.921C40.46,46.548 40.022,46.002 39.723,45.331C39,43.712 39.355,41.978 40.515,41.46C41.409,41.061 42.522,41.501 43.316,42.465C44.181,40.961 45.28,39.376 46,39.54C45.537,38.56 45.285,38.494 45.26,37.41C45.262,34.996 46.368,33.71 48.26,32.21C49.26,31.412 50.69,31.412 51.69,32.21C53.573,33.717 54.676,34.998 54.69,37.41C54.693,38.487 54.472,38.553 54.04,39.54C54.745,39.379 55.804,40.898 56.65,42.374C57.646,41.38 58.992,41.066 59.882,41.699C60.917,42.435 60.944,44.176 59.943,45.583C59.643,46.005 59.284,46.35 58.9,46.606C61.048,50.889 61.326,55.404 59.712,59.791C60.044,60.047 60.348,60.376 60.601,60.767C61.519,62.19 61.398,63.904 60.331,64.593C59.472,65.147 58.26,64.869 57.341,63.998C56.498,64.994 55.264,66.263 54.21,66.59C53.138,66.922 51.435,67.563 49.82,67.553C48.301,67.544 46.869,66.903 45.86,66.59C44.798,66.261 43.552,64.979 42.706,63.979Z".into()).unwrap();
-
-        let canvas: web_sys::HtmlCanvasElement =
-            canvas
-                .dyn_into::<web_sys::HtmlCanvasElement>()
-                .map_err(|_| ())
-                .unwrap();
-
-        let context = canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<web_sys::CanvasRenderingContext2d>()
-            .unwrap();
-        context.save();
+        let context = canvas::get_context();
+        let canvas_size = &self.transform.canvas_size;
+        context.clear_rect(0.0, 0.0, canvas_size.width, canvas_size.height);
+        if let Some(background) = &self.background {
+            canvas::paint_background(background);
+        }
+    }
+
+    fn restore_background(&self) {
+        // Erasing leaves transparent pixels. They are painted over so the background stays solid.
+        if self.turtle.pen_mode != PenMode::Erase {
+            return;
+        }
+        if let Some(background) = &self.background {
+            canvas::paint_background(background);
+        }
+    }
+    fn render_turtles(&self) {
+        self.render_turtle(&self.turtle);
+        for turtle in self.turtles.values() {
+            self.render_turtle(turtle);
+        }
+    }
 
-        // Unfortunately, the Path2d struct in web_sys does not provide a direct way to find its dimensions.
-        // This is because a Path2d object represents a path made up of points, arcs, or curves,
-        // and it does not have a specific width or height like a Rect or Image object.
-        // Values 50.0 are result of experimentation.
-        let _ = context.translate(self.turtle.x - 50.0, self.turtle.y - 50.0);
-        // Applying rotation and scaling is trickier.
-        // Just translating to correct location is good enough.
-        context.fill_with_path_2d(&path);
-        context.restore();
+    fn render_turtle(&self, turtle: &Turtle) {
+        if !turtle.visible {
+            return;
+        }
+        canvas::draw_turtle(turtle, &self.transform.to_canvas(&turtle.location()));
     }
 
     /// Function should be invoked only once per script.
     pub fn interpret_script(
         &mut self,
-        block: &mut Vec<Stmt>,
+        block: &[Stmt],
     ) -> Result<(), InterpreterError> {
         self.clear_canvas();
+        self.declare_procedures(block)?;
         self.interpret_block(block)?;
-        self.render_turtle();
+        self.render_turtles();
         Ok(())
     }
 
     fn interpret_block(
         &mut self,
-        statements: &mut Vec<Stmt>,
+        statements: &[Stmt],
     ) -> Result<(), InterpreterError> {
         self.interpret_statements(statements)
     }
 
-    fn evaluate(&mut self, expr: &mut Expr) -> Result<Obj, InterpreterError> {
+    fn declare_procedures(&mut self, statements: &[Stmt]) -> Result<(), InterpreterError> {
+        // Procedures are declared before the script runs so they can be called before their declaration.
+        for statement in statements {
+            if let Stmt::ProcedureDeclaration(_) = statement {
+                statement.accept(self)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn call_procedure(&mut self, name: &str, arguments: &[Expr]) -> Result<Option<Obj>, InterpreterError> {
+        let procedure = match self.procedures.get(name) {
+            // Shared so a call doesn't copy the body.
+            Some(procedure) => Rc::clone(procedure),
+            None => return Err(InterpreterError::Runtime {
+                message: format!("Procedure `{}` is not declared.", name),
+            }),
+        };
+        if self.call_depth >= self.max_recursion_depth {
+            return Err(InterpreterError::Runtime {
+                message: format!("Too many nested procedure calls while calling `{}`. Maximum depth is {}.", name, self.max_recursion_depth),
+            });
+        }
+        // Arguments are evaluated in the scope of the caller.
+        let mut frame = HashMap::new();
+        for (parameter, argument) in procedure.parameters.iter().zip(arguments) {
+            let value = self.evaluate(argument)?;
+            frame.insert(parameter.clone(), value);
+        }
+        self.environment.push_frame(frame);
+        self.call_depth += 1;
+        let result = procedure.body.accept(self);
+        self.call_depth -= 1;
+        self.environment.pop_frame();
+        result?;
+        match self.return_value.take() {
+            Some(ProcedureExit::Output(value)) => Ok(Some(value)),
+            _ => Ok(None),
+        }
+    }
+
+    fn call_builtin(&mut self, builtin: Builtin, arguments: Vec<Obj>) -> Result<Obj, InterpreterError> {
+        // Parser guarantees the number of arguments matches the arity of the builtin.
+        let name = builtin.name();
+        match builtin {
+            Builtin::RepCount => match self.repeat_counts.last() {
+                Some(count) => Ok(Obj::Num(*count as f64)),
+                // Same as in UCBLogo, REPCOUNT outside of a loop outputs -1.
+                None => Ok(Obj::Num(-1.0)),
+            },
+            Builtin::PenDownP => Ok(Obj::Bool(self.turtle.pen_down)),
+            Builtin::XCor => Ok(Obj::Num(self.turtle.x)),
+            Builtin::YCor => Ok(Obj::Num(self.turtle.y)),
+            Builtin::Pos => Ok(Obj::List(vec![Obj::Num(self.turtle.x), Obj::Num(self.turtle.y)])),
+            Builtin::Heading => Ok(Obj::Num(self.turtle.heading)),
+            Builtin::Towards => {
+                let target = expect_location(arguments[0].clone(), name)?;
+                Ok(Obj::Num(self.turtle.towards(&target)))
+            },
+            Builtin::DistanceXY => {
+                let target = Location2d {
+                    x: expect_number(arguments[0].clone(), name)?,
+                    y: expect_number(arguments[1].clone(), name)?,
+                };
+                Ok(Obj::Num(self.turtle.distance(&target)))
+            },
+            Builtin::PenColor => Ok(Obj::Color(self.turtle.color)),
+            Builtin::PenSize => Ok(Obj::Num(self.turtle.pen_size)),
+            Builtin::Who => match self.active_turtles.as_slice() {
+                [id] => Ok(turtle_id_to_obj(id)),
+                ids => Ok(Obj::List(ids.iter().map(|id| turtle_id_to_obj(id)).collect())),
+            },
+
+            Builtin::First => primitives::first(&arguments[0]),
+            Builtin::ButFirst => primitives::butfirst(&arguments[0]),
+            Builtin::Last => primitives::last(&arguments[0]),
+            Builtin::ButLast => primitives::butlast(&arguments[0]),
+            Builtin::Item => primitives::item(expect_number(arguments[0].clone(), name)?, &arguments[1]),
+            Builtin::FPut => primitives::fput(&arguments[0], &arguments[1]),
+            Builtin::LPut => primitives::lput(&arguments[0], &arguments[1]),
+            Builtin::Count => Ok(primitives::count(&arguments[0])),
+            Builtin::EmptyP => Ok(primitives::emptyp(&arguments[0])),
+            Builtin::Sentence => Ok(primitives::sentence(&arguments[0], &arguments[1])),
+            Builtin::Word => primitives::word(&arguments[0], &arguments[1]),
+            Builtin::List => Ok(primitives::list(&arguments[0], &arguments[1])),
+
+            Builtin::Sin => Ok(primitives::sin(expect_number(arguments[0].clone(), name)?)),
+            Builtin::Cos => Ok(primitives::cos(expect_number(arguments[0].clone(), name)?)),
+            Builtin::Tan => Ok(primitives::tan(expect_number(arguments[0].clone(), name)?)),
+            Builtin::ArcTan => Ok(primitives::arctan(expect_number(arguments[0].clone(), name)?)),
+            Builtin::Sqrt => primitives::sqrt(expect_number(arguments[0].clone(), name)?),
+            Builtin::Power => {
+                let base = expect_number(arguments[0].clone(), name)?;
+                let exponent = expect_number(arguments[1].clone(), name)?;
+                Ok(Obj::Num(base.powf(exponent)))
+            },
+            Builtin::Exp => Ok(Obj::Num(expect_number(arguments[0].clone(), name)?.exp())),
+            Builtin::Ln => primitives::ln(expect_number(arguments[0].clone(), name)?),
+            Builtin::Abs => Ok(Obj::Num(expect_number(arguments[0].clone(), name)?.abs())),
+            Builtin::Int => Ok(Obj::Num(expect_number(arguments[0].clone(), name)?.trunc())),
+            Builtin::Round => Ok(Obj::Num(expect_number(arguments[0].clone(), name)?.round())),
+            Builtin::Remainder => primitives::remainder(
+                expect_number(arguments[0].clone(), name)?,
+                expect_number(arguments[1].clone(), name)?,
+            ),
+            Builtin::Modulo => primitives::modulo(
+                expect_number(arguments[0].clone(), name)?,
+                expect_number(arguments[1].clone(), name)?,
+            ),
+            Builtin::Random => {
+                let bound = expect_number(arguments[0].clone(), name)?.round();
+                if bound < 1.0 {
+                    return Err(InterpreterError::Runtime {
+                        message: format!("RANDOM doesn't like {} as input. Input must be a positive integer.", bound),
+                    });
+                }
+                Ok(Obj::Num(self.random.below(bound as u64) as f64))
+            },
+            Builtin::Pi => Ok(Obj::Num(std::f64::consts::PI)),
+            Builtin::Hsv => Ok(Obj::Color(Color::from_hsv(
+                expect_number(arguments[0].clone(), name)?,
+                expect_number(arguments[1].clone(), name)?,
+                expect_number(arguments[2].clone(), name)?,
+            ))),
+        }
+    }
+
+    fn select_turtle(&mut self, id: &str) {
+        if self.current_turtle == id {
+            return;
+        }
+        // Turtles are created at home the first time they are picked.
+        let next = self.turtles.remove(id).unwrap_or_default();
+        let previous = std::mem::replace(&mut self.turtle, next);
+        let previous_id = std::mem::replace(&mut self.current_turtle, id.to_string());
+        self.turtles.insert(previous_id, previous);
+    }
+
+    fn set_active_turtles(&mut self, ids: Vec<String>) {
+        // Queries such as XCOR read the first active turtle.
+        self.select_turtle(&ids[0]);
+        self.active_turtles = ids;
+    }
+
+    fn for_each_active_turtle<F>(&mut self, mut action: F)
+    where
+        F: FnMut(&mut Interpreter),
+    {
+        let _ = self.try_for_each_active_turtle(|interpreter| {
+            action(interpreter);
+            Ok(())
+        });
+    }
+
+    fn try_for_each_active_turtle<F>(&mut self, mut action: F) -> Result<(), InterpreterError>
+    where
+        F: FnMut(&mut Interpreter) -> Result<(), InterpreterError>,
+    {
+        // Inputs are evaluated once, before this, so every turtle gets the same values.
+        let current = self.current_turtle.clone();
+        let mut result = Ok(());
+        for id in self.active_turtles.clone() {
+            self.select_turtle(&id);
+            result = action(self);
+            if result.is_err() {
+                break;
+            }
+        }
+        self.select_turtle(&current);
+        result
+    }
+
+    fn turtles_to_stamp(&mut self) -> Vec<Turtle> {
+        let mut turtles = Vec::new();
+        self.for_each_active_turtle(|interpreter| turtles.push(interpreter.turtle.clone()));
+        turtles
+    }
+
+    fn evaluate_turtle_ids(&mut self, expr: &Expr, command: &str) -> Result<Vec<String>, InterpreterError> {
+        let values = match self.evaluate(expr)? {
+            Obj::List(items) => items,
+            value => vec![value],
+        };
+        let mut ids: Vec<String> = Vec::new();
+        for value in values {
+            let id = turtle_id(value, command)?;
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        if ids.is_empty() {
+            return Err(InterpreterError::Runtime {
+                message: format!("{} expected at least one turtle.", command),
+            });
+        }
+        Ok(ids)
+    }
+
+    fn move_turtle(&mut self, distance: f64) -> Result<(), InterpreterError> {
+        self.move_turtle_to(self.turtle.ahead(distance))
+    }
+
+    fn move_turtle_to(&mut self, to: Location2d) -> Result<(), InterpreterError> {
+        if !to.x.is_finite() || !to.y.is_finite() {
+            return Err(InterpreterError::Runtime {
+                message: "The turtle can't move that far.".to_string(),
+            });
+        }
+        let from = self.turtle.location();
+        let lines = match self.boundary_mode {
+            BoundaryMode::Window => vec![(from, to)],
+            BoundaryMode::Wrap => self.transform.wrap_line(&from, &to),
+            BoundaryMode::Fence => {
+                if !self.transform.contains(&to) {
+                    // Same as in UCBLogo, the turtle stays where it was.
+                    return Err(InterpreterError::Runtime {
+                        message: format!("Turtle out of bounds at [{} {}].", to.x, to.y),
+                    });
+                }
+                vec![(from, to)]
+            },
+        };
+        for (start, end) in lines {
+            self.trace_line(start, end);
+        }
+        Ok(())
+    }
+
+    fn trace_line(&mut self, from: Location2d, to: Location2d) {
+        // Turtle moves regardless of the pen, it only draws when the pen is down.
+        if from != self.turtle.location() {
+            // Wrapped line jumps to the opposite edge.
+            self.record_path_segment(PathSegment::MoveTo(self.transform.to_canvas(&from)));
+        }
+        self.record_path_segment(PathSegment::LineTo(self.transform.to_canvas(&to)));
+        self.turtle.x = to.x;
+        self.turtle.y = to.y;
+        if self.turtle.pen_down {
+            canvas::draw_line(&self.turtle, self.background.is_some(), &self.transform.to_canvas(&from), &self.transform.to_canvas(&to));
+            self.restore_background();
+        }
+    }
+
+    fn draw_arc(&mut self, angle: f64, radius: f64) {
+        // Arc is centred on the turtle, starts at its heading and the turtle doesn't move.
+        let centre = self.transform.to_canvas(&self.turtle.location());
+        let start_angle = self.transform.to_canvas_angle(self.turtle.heading);
+        let end_angle = self.transform.to_canvas_angle(self.turtle.heading + angle);
+        let anticlockwise = angle < 0.0;
+        // In a filled path, the arc is its own piece. Without the moves, canvas would join
+        // the arc to the centre and continue the path from the end of the arc.
+        self.record_path_segment(PathSegment::MoveTo(self.transform.to_canvas(&self.turtle.ahead(radius))));
+        self.record_path_segment(PathSegment::Arc { centre, radius, start_angle, end_angle, anticlockwise });
+        self.record_path_segment(PathSegment::MoveTo(centre));
+        if self.turtle.pen_down {
+            canvas::draw_arc(&self.turtle, self.background.is_some(), &centre, radius, start_angle, end_angle, anticlockwise);
+            self.restore_background();
+        }
+    }
+
+    fn record_path_segment(&mut self, segment: PathSegment) {
+        // Called before the turtle moves. Its path starts where it is when it first moves inside the block.
+        // Outer FILLED blocks include everything traced by the inner ones.
+        let start = PathSegment::MoveTo(self.transform.to_canvas(&self.turtle.location()));
+        for paths in self.filled_paths.iter_mut() {
+            let path = paths.entry(self.current_turtle.clone()).or_insert_with(|| vec![start.clone()]);
+            path.push(segment.clone());
+        }
+    }
+
+
+    fn evaluate(&mut self, expr: &Expr) -> Result<Obj, InterpreterError> {
         expr.accept(self)
     }
+
+    fn evaluate_number(&mut self, expr: &Expr, command: &str) -> Result<f64, InterpreterError> {
+        let value = self.evaluate(expr)?;
+        expect_number(value, command)
+    }
+
+    fn evaluate_color(&mut self, expr: &Expr, command: &str) -> Result<Color, InterpreterError> {
+        let value = self.evaluate(expr)?;
+        self.to_color(value, command)
+    }
+
+    fn to_color(&self, value: Obj, command: &str) -> Result<Color, InterpreterError> {
+        // Colour can be a hex colour, a CSS name, a palette index or a list of red, green, blue and optional alpha.
+        let color = match &value {
+            Obj::Color(color) => Some(*color),
+            Obj::Word(word) if word.starts_with('#') => Color::from_hex(word),
+            Obj::Word(word) => match primitives::as_number(&value) {
+                Some(index) => self.palette_color(index),
+                None => Color::from_name(word),
+            },
+            Obj::Num(index) => self.palette_color(*index),
+            Obj::List(items) if items.len() == 3 || items.len() == 4 => {
+                let mut channels = Vec::new();
+                for item in items {
+                    match primitives::as_number(item) {
+                        Some(channel) if (0.0..=255.0).contains(&channel) => channels.push(channel.round() as u8),
+                        _ => return Err(InterpreterError::Runtime {
+                            message: format!("{} expected channels from 0 to 255 but got {}.", command, value),
+                        }),
+                    }
+                }
+                Some(Color { red: channels[0], green: channels[1], blue: channels[2], alpha: *channels.get(3).unwrap_or(&255) })
+            },
+            _ => None,
+        };
+        color.ok_or_else(|| InterpreterError::Runtime {
+            message: format!("{} doesn't like {} as a color.", command, value),
+        })
+    }
+
+    fn palette_color(&self, index: f64) -> Option<Color> {
+        if index.fract() != 0.0 || index < 0.0 {
+            return None;
+        }
+        self.palette.get(index as usize).copied()
+    }
+
+    fn evaluate_bool(&mut self, expr: &Expr, command: &str) -> Result<bool, InterpreterError> {
+        match self.evaluate(expr)? {
+            Obj::Bool(value) => Ok(value),
+            Obj::Word(word) if word.eq_ignore_ascii_case("true") => Ok(true),
+            Obj::Word(word) if word.eq_ignore_ascii_case("false") => Ok(false),
+            other => Err(InterpreterError::Runtime {
+                message: format!("{} expected true or false but got {:?}.", command, other),
+            }),
+        }
+    }
+}
+
+fn expect_number(value: Obj, command: &str) -> Result<f64, InterpreterError> {
+    match primitives::as_number(&value) {
+        Some(number) => Ok(number),
+        None => Err(InterpreterError::Runtime {
+            message: format!("{} expected a number but got {}.", command, value),
+        }),
+    }
+}
+
+fn print_form(value: &Obj) -> String {
+    // Outer brackets are omitted, nested lists keep theirs.
+    match value {
+        Obj::List(items) => items.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(" "),
+        value => value.to_string(),
+    }
+}
+
+fn expect_location(value: Obj, command: &str) -> Result<Location2d, InterpreterError> {
+    match &value {
+        Obj::List(items) if items.len() == 2 => Ok(Location2d {
+            x: expect_number(items[0].clone(), command)?,
+            y: expect_number(items[1].clone(), command)?,
+        }),
+        _ => Err(InterpreterError::Runtime {
+            message: format!("{} expected a list of two numbers but got {}.", command, value),
+        }),
+    }
+}
+
+fn turtle_id(value: Obj, command: &str) -> Result<String, InterpreterError> {
+    // Numbers and numeric words name the same turtle, e.g. `TELL 1` and `TELL "1`.
+    match &value {
+        Obj::Num(number) if *number >= 0.0 && number.fract() == 0.0 => Ok(number.to_string()),
+        Obj::Word(word) if !word.is_empty() => Ok(word.to_lowercase()),
+        _ => Err(InterpreterError::Runtime {
+            message: format!("{} expected a turtle number or name but got {}.", command, value),
+        }),
+    }
+}
+
+fn turtle_id_to_obj(id: &str) -> Obj {
+    match id.parse::<f64>() {
+        Ok(number) => Obj::Num(number),
+        Err(_) => Obj::Word(id.to_string()),
+    }
+}
+
+fn unused_value_error(value: &Obj) -> InterpreterError {
+    InterpreterError::Runtime {
+        message: format!("You don't say what to do with {}.", value),
+    }
+}
+
+/// Stops any loop that runs more than MAX_LOOP_ITERATIONS times.
+fn check_loop_iteration(iteration: usize) -> Result<(), InterpreterError> {
+    if iteration > MAX_LOOP_ITERATIONS {
+        return Err(InterpreterError::Runtime {
+            message: format!("Loop did not finish after {} iterations.", MAX_LOOP_ITERATIONS),
+        });
+    }
+    Ok(())
+}
+
+fn literal_to_obj(literal: &Literal) -> Obj {
+    match literal {
+        Literal::Number(number) => Obj::Num(*number),
+        Literal::Color(color) => Obj::Color(*color),
+        Literal::Word(word) => Obj::Word(word.to_string()),
+        Literal::List(items) => Obj::List(items.iter().map(literal_to_obj).collect()),
+    }
 }
 
 impl StmtVisitor for Interpreter {
     type Result = Result<(), InterpreterError>;
 
-    fn visit_block(&mut self, stmts: &mut Vec<Stmt>) -> Self::Result {
+    fn visit_block(&mut self, stmts: &[Stmt]) -> Self::Result {
         self.interpret_block(stmts)
     }
 
-    fn visit_expression(&mut self, expr: &mut Expr) -> Self::Result {
-        self.evaluate(expr).map(|_| ())
+    fn visit_expression(&mut self, expr: &Expr) -> Self::Result {
+        // Same as in UCBLogo, a value in place of a command is a mistake.
+        let value = self.evaluate(expr)?;
+        Err(unused_value_error(&value))
     }
 
-    fn visit_repeat(&mut self, repeat_stmt: &mut RepeatStmt) -> Self::Result {
-        for _ in 0..repeat_stmt.count {
-            if self.return_value != None {
+    fn visit_repeat(&mut self, repeat_stmt: &RepeatStmt) -> Self::Result {
+        let count = self.evaluate_number(&repeat_stmt.count, "REPEAT")?.round() as usize;
+        self.repeat_counts.push(0);
+        let mut result = Ok(());
+        for iteration in 1..=count {
+            if self.return_value.is_some() || result.is_err() {
                 break;
             }
-            repeat_stmt.body.accept(self)?;
+            *self.repeat_counts.last_mut().unwrap() = iteration;
+            result = check_loop_iteration(iteration).and_then(|_| repeat_stmt.body.accept(self));
+        }
+        self.repeat_counts.pop();
+        result
+    }
+
+    fn visit_while(&mut self, stmt: &WhileStmt) -> Self::Result {
+        let mut iterations = 0;
+        while self.return_value.is_none() && self.evaluate_bool(&stmt.condition, "WHILE")? {
+            iterations += 1;
+            check_loop_iteration(iterations)?;
+            stmt.body.accept(self)?;
         }
         Ok(())
     }
 
-    fn visit_set_color(&mut self, stmt: &mut SetColorStmt) -> Self::Result {
-        self.turtle.color = Cow::Owned(stmt.color.to_string());
+    fn visit_forever(&mut self, stmt: &ForeverStmt) -> Self::Result {
+        self.repeat_counts.push(0);
+        let mut result = Ok(());
+        for iteration in 1.. {
+            if self.return_value.is_some() || result.is_err() {
+                break;
+            }
+            *self.repeat_counts.last_mut().unwrap() = iteration;
+            result = check_loop_iteration(iteration).and_then(|_| stmt.body.accept(self));
+        }
+        self.repeat_counts.pop();
+        result
+    }
+
+    fn visit_for(&mut self, stmt: &ForStmt) -> Self::Result {
+        let start = self.evaluate_number(&stmt.start, "FOR")?;
+        let end = self.evaluate_number(&stmt.end, "FOR")?;
+        let step = match &stmt.step {
+            Some(step) => self.evaluate_number(step, "FOR")?,
+            None if start <= end => 1.0,
+            None => -1.0,
+        };
+        if step == 0.0 {
+            return Err(InterpreterError::Runtime {
+                message: "FOR loop step must not be zero.".into(),
+            });
+        }
+        // Loop variable is local to the loop.
+        self.environment.push_frame(HashMap::from([(stmt.variable.clone(), Obj::Num(start))]));
+        let mut result = Ok(());
+        // Counting steps instead of adding them up keeps `FOR [i 0 1 0.1]` from drifting past 1.
+        for k in 0.. {
+            let value = start + k as f64 * step;
+            let in_range = if step > 0.0 { value <= end } else { value >= end };
+            if !in_range || self.return_value.is_some() || result.is_err() {
+                break;
+            }
+            self.environment.set(&stmt.variable, Obj::Num(value));
+            result = check_loop_iteration(k + 1).and_then(|_| stmt.body.accept(self));
+        }
+        self.environment.pop_frame();
+        result
+    }
+
+    fn visit_if(&mut self, stmt: &IfStmt) -> Self::Result {
+        if self.evaluate_bool(&stmt.condition, "IF")? {
+            stmt.then_branch.accept(self)
+        } else if let Some(else_branch) = &stmt.else_branch {
+            else_branch.accept(self)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn visit_set_color(&mut self, stmt: &SetColorStmt) -> Self::Result {
+        let color = self.evaluate_color(&stmt.color, "COLOR")?;
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.color = color);
         Ok(())
     }
 
-    fn visit_move_forward(&mut self, stmt: &mut MoveForwardStmt) -> Self::Result {
-        let document = web_sys::window().unwrap().document().unwrap();
-        let canvas = document.get_element_by_id(CANVAS_ID).unwrap();
+    fn visit_set_background(&mut self, stmt: &SetBackgroundStmt) -> Self::Result {
+        let color = self.evaluate_color(&stmt.color, "SETBACKGROUND")?;
+        canvas::set_background(self.background, &color);
+        self.background = Some(color);
+        Ok(())
+    }
 
-        let canvas: web_sys::HtmlCanvasElement =
-            canvas
-                .dyn_into::<web_sys::HtmlCanvasElement>()
-                .map_err(|_| ())
-                .unwrap();
+    fn visit_set_palette(&mut self, stmt: &SetPaletteStmt) -> Self::Result {
+        let index = self.evaluate_number(&stmt.index, "SETPALETTE")?;
+        if index.fract() != 0.0 || !(0.0..16.0).contains(&index) {
+            return Err(InterpreterError::Runtime {
+                message: format!("SETPALETTE expected an index from 0 to 15 but got {}.", index),
+            });
+        }
+        self.palette[index as usize] = self.evaluate_color(&stmt.color, "SETPALETTE")?;
+        Ok(())
+    }
 
-        let context = canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<web_sys::CanvasRenderingContext2d>()
-            .unwrap();
+    fn visit_set_pen_size(&mut self, stmt: &SetPenSizeStmt) -> Self::Result {
+        let size = self.evaluate_number(&stmt.size, "SETPENSIZE")?;
+        if size < 0.0 {
+            return Err(InterpreterError::Runtime {
+                message: format!("SETPENSIZE doesn't like {} as input.", size),
+            });
+        }
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.pen_size = size);
+        Ok(())
+    }
 
-        context.begin_path();
-        context.move_to(self.turtle.x, self.turtle.y);
-        self.turtle.x = self.turtle.x + stmt.distance * self.turtle.phi.cos();
-        self.turtle.y = self.turtle.y - stmt.distance * self.turtle.phi.sin();
-        context.line_to(self.turtle.x, self.turtle.y);
-        context.set_stroke_style(
-            &JsValue::from_str(self.turtle.color.as_ref())
-        );
-        context.stroke();
+    fn visit_set_line_cap(&mut self, stmt: &SetLineCapStmt) -> Self::Result {
+        let value = self.evaluate(&stmt.cap)?;
+        match LineCap::from_name(&value.to_string()) {
+            Some(cap) => {
+                self.for_each_active_turtle(|interpreter| interpreter.turtle.line_cap = cap);
+                Ok(())
+            },
+            None => Err(InterpreterError::Runtime {
+                message: format!("SETLINECAP expected butt, round or square but got {}.", value),
+            }),
+        }
+    }
+
+    fn visit_set_line_join(&mut self, stmt: &SetLineJoinStmt) -> Self::Result {
+        let value = self.evaluate(&stmt.join)?;
+        match LineJoin::from_name(&value.to_string()) {
+            Some(join) => {
+                self.for_each_active_turtle(|interpreter| interpreter.turtle.line_join = join);
+                Ok(())
+            },
+            None => Err(InterpreterError::Runtime {
+                message: format!("SETLINEJOIN expected miter, round or bevel but got {}.", value),
+            }),
+        }
+    }
+
+    fn visit_set_dash(&mut self, stmt: &SetDashStmt) -> Self::Result {
+        let items = match self.evaluate(&stmt.pattern)? {
+            Obj::List(items) => items,
+            other => return Err(InterpreterError::Runtime {
+                message: format!("SETDASH expected a list of lengths but got {}.", other),
+            }),
+        };
+        let mut dash = Vec::new();
+        for item in items {
+            let length = expect_number(item, "SETDASH")?;
+            if length < 0.0 {
+                return Err(InterpreterError::Runtime {
+                    message: format!("SETDASH doesn't like {} as a length.", length),
+                });
+            }
+            dash.push(length);
+        }
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.dash = dash.clone());
         Ok(())
     }
 
-    fn visit_move_backward(&mut self, stmt: &mut MoveBackwardStmt) -> Self::Result {
-        let document = web_sys::window().unwrap().document().unwrap();
-        let canvas = document.get_element_by_id(CANVAS_ID).unwrap();
+    fn visit_move_forward(&mut self, stmt: &MoveForwardStmt) -> Self::Result {
+        let distance = self.evaluate_number(&stmt.distance, "FD")?;
+        self.try_for_each_active_turtle(|interpreter| interpreter.move_turtle(distance))
+    }
+
+    fn visit_move_backward(&mut self, stmt: &MoveBackwardStmt) -> Self::Result {
+        let distance = self.evaluate_number(&stmt.distance, "BK")?;
+        self.try_for_each_active_turtle(|interpreter| interpreter.move_turtle(-distance))
+    }
 
-        let canvas: web_sys::HtmlCanvasElement =
-            canvas
-                .dyn_into::<web_sys::HtmlCanvasElement>()
-                .map_err(|_| ())
-                .unwrap();
+    fn visit_rotate_right(&mut self, stmt: &RotateRightStmt) -> Self::Result {
+        let angular_distance = self.evaluate_number(&stmt.angular_distance, "RT")?;
+        self.for_each_active_turtle(|interpreter| {
+            interpreter.turtle.set_heading(interpreter.turtle.heading + angular_distance);
+        });
+        Ok(())
+    }
 
-        let context = canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<web_sys::CanvasRenderingContext2d>()
-            .unwrap();
+    fn visit_rotate_left(&mut self, stmt: &RotateLeftStmt) -> Self::Result {
+        let angular_distance = self.evaluate_number(&stmt.angular_distance, "LT")?;
+        self.for_each_active_turtle(|interpreter| {
+            interpreter.turtle.set_heading(interpreter.turtle.heading - angular_distance);
+        });
+        Ok(())
+    }
 
-        context.begin_path();
-        context.move_to(self.turtle.x, self.turtle.y);
-        self.turtle.x = self.turtle.x - stmt.distance * self.turtle.phi.cos();
-        self.turtle.y = self.turtle.y + stmt.distance * self.turtle.phi.sin();
-        context.line_to(self.turtle.x, self.turtle.y);
-        context.set_stroke_style(
-            &JsValue::from_str(self.turtle.color.as_ref())
-        );
-        context.stroke();
+    fn visit_make(&mut self, stmt: &MakeStmt) -> Self::Result {
+        let value = self.evaluate(&stmt.value)?;
+        self.environment.set(&stmt.name, value);
+        Ok(())
+    }
+
+    fn visit_print(&mut self, stmt: &PrintStmt) -> Self::Result {
+        let value = self.evaluate(&stmt.value)?;
+        let text = match stmt.style {
+            PrintStyle::Show => value.to_string(),
+            _ => print_form(&value),
+        };
+        self.output.push_str(&text);
+        if stmt.style != PrintStyle::Type {
+            self.output.push('\n');
+        }
+        Ok(())
+    }
+
+    fn visit_rerandom(&mut self, stmt: &RerandomStmt) -> Self::Result {
+        let seed = self.evaluate_number(&stmt.seed, "RERANDOM")?;
+        self.set_random_seed(seed as i64 as u64);
+        Ok(())
+    }
+
+    fn visit_set_recursion_depth(&mut self, stmt: &SetRecursionDepthStmt) -> Self::Result {
+        let depth = self.evaluate_number(&stmt.depth, "SETRECURSIONDEPTH")?;
+        if depth.fract() != 0.0 || depth < 1.0 || depth > RECURSION_DEPTH_CEILING as f64 {
+            return Err(InterpreterError::Runtime {
+                message: format!("SETRECURSIONDEPTH expected a whole number from 1 to {} but got {}.", RECURSION_DEPTH_CEILING, depth),
+            });
+        }
+        self.max_recursion_depth = depth as usize;
+        Ok(())
+    }
+
+    fn visit_procedure_declaration(&mut self, stmt: &ProcedureDeclarationStmt) -> Self::Result {
+        self.procedures.insert(stmt.name.clone(), Rc::new(stmt.clone()));
         Ok(())
     }
 
-    fn visit_rotate_right(&mut self, stmt: &mut RotateRightStmt) -> Self::Result {
-        self.turtle.phi = self.turtle.phi - stmt.angular_distance * std::f64::consts::PI/180.0;
+    fn visit_procedure_call(&mut self, stmt: &ProcedureCallStmt) -> Self::Result {
+        // Procedure called as a command must not OUTPUT a value.
+        match self.call_procedure(&stmt.name, &stmt.arguments)? {
+            Some(value) => Err(unused_value_error(&value)),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_arc(&mut self, stmt: &ArcStmt) -> Self::Result {
+        let angle = self.evaluate_number(&stmt.angle, "ARC")?;
+        let radius = self.evaluate_number(&stmt.radius, "ARC")?;
+        if radius < 0.0 {
+            return Err(InterpreterError::Runtime {
+                message: format!("ARC doesn't like {} as radius.", radius),
+            });
+        }
+        self.for_each_active_turtle(|interpreter| interpreter.draw_arc(angle, radius));
+        Ok(())
+    }
+
+    fn visit_filled(&mut self, stmt: &FilledStmt) -> Self::Result {
+        let color = self.evaluate_color(&stmt.color, "FILLED")?;
+        self.filled_paths.push(BTreeMap::new());
+        let result = stmt.body.accept(self);
+        let paths = self.filled_paths.pop().unwrap();
+        result?;
+        for path in paths.values() {
+            canvas::fill_path(&color, path);
+        }
+        Ok(())
+    }
+
+    fn visit_fill(&mut self) -> Self::Result {
+        self.for_each_active_turtle(|interpreter| {
+            canvas::flood_fill_at(&interpreter.turtle, &interpreter.transform.to_canvas(&interpreter.turtle.location()));
+        });
         Ok(())
     }
 
-    fn visit_rotate_left(&mut self, stmt: &mut RotateLeftStmt) -> Self::Result {
-        self.turtle.phi = self.turtle.phi + stmt.angular_distance * std::f64::consts::PI/180.0;
+    fn visit_label(&mut self, stmt: &LabelStmt) -> Self::Result {
+        let text = print_form(&self.evaluate(&stmt.text)?);
+        // Heading 90 gives horizontal text, same as in UCBLogo.
+        self.for_each_active_turtle(|interpreter| {
+            let angle = interpreter.transform.to_canvas_angle(interpreter.turtle.heading);
+            let at = interpreter.transform.to_canvas(&interpreter.turtle.location());
+            canvas::draw_label(&interpreter.turtle, interpreter.background.is_some(), &text, &at, angle);
+            interpreter.restore_background();
+        });
+        Ok(())
+    }
+
+    fn visit_set_label_height(&mut self, stmt: &SetLabelHeightStmt) -> Self::Result {
+        let height = self.evaluate_number(&stmt.height, "SETLABELHEIGHT")?;
+        if height <= 0.0 {
+            return Err(InterpreterError::Runtime {
+                message: format!("SETLABELHEIGHT doesn't like {} as input.", height),
+            });
+        }
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.label_height = height);
+        Ok(())
+    }
+
+    fn visit_set_font(&mut self, stmt: &SetFontStmt) -> Self::Result {
+        // List allows font names with spaces, e.g. SETFONT [Times New Roman].
+        let font = print_form(&self.evaluate(&stmt.font)?);
+        if font.trim().is_empty() {
+            return Err(InterpreterError::Runtime {
+                message: "SETFONT doesn't like an empty font name.".into(),
+            });
+        }
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.font = font.clone());
+        Ok(())
+    }
+
+    fn visit_set_position(&mut self, stmt: &SetPositionStmt) -> Self::Result {
+        let position = self.evaluate(&stmt.position)?;
+        let to = expect_location(position, "SETPOS")?;
+        self.try_for_each_active_turtle(|interpreter| interpreter.move_turtle_to(to))
+    }
+
+    fn visit_set_xy(&mut self, stmt: &SetXYStmt) -> Self::Result {
+        let x = match &stmt.x {
+            Some(x) => Some(self.evaluate_number(x, "SETXY")?),
+            None => None,
+        };
+        let y = match &stmt.y {
+            Some(y) => Some(self.evaluate_number(y, "SETXY")?),
+            None => None,
+        };
+        self.try_for_each_active_turtle(|interpreter| {
+            let to = Location2d {
+                x: x.unwrap_or(interpreter.turtle.x),
+                y: y.unwrap_or(interpreter.turtle.y),
+            };
+            interpreter.move_turtle_to(to)
+        })
+    }
+
+    fn visit_set_heading(&mut self, stmt: &SetHeadingStmt) -> Self::Result {
+        let heading = self.evaluate_number(&stmt.heading, "SETHEADING")?;
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.set_heading(heading));
+        Ok(())
+    }
+
+    fn visit_home(&mut self) -> Self::Result {
+        // Same as moving, HOME draws a line when the pen is down.
+        let home = Turtle::default();
+        self.try_for_each_active_turtle(|interpreter| {
+            interpreter.move_turtle_to(home.location())?;
+            interpreter.turtle.heading = home.heading;
+            Ok(())
+        })
+    }
+
+    fn visit_clean(&mut self) -> Self::Result {
+        self.clear_canvas();
+        Ok(())
+    }
+
+    fn visit_clear_screen(&mut self) -> Self::Result {
+        self.clear_canvas();
+        // Every turtle goes home, not only the active ones, so the picture starts over.
+        let home = Turtle::default();
+        for turtle in std::iter::once(&mut self.turtle).chain(self.turtles.values_mut()) {
+            turtle.x = home.x;
+            turtle.y = home.y;
+            turtle.heading = home.heading;
+        }
+        Ok(())
+    }
+
+    fn visit_pen_up(&mut self) -> Self::Result {
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.pen_down = false);
+        Ok(())
+    }
+
+    fn visit_pen_down(&mut self) -> Self::Result {
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.pen_down = true);
+        Ok(())
+    }
+
+    fn visit_set_pen_mode(&mut self, mode: &PenMode) -> Self::Result {
+        let mode = *mode;
+        self.for_each_active_turtle(|interpreter| {
+            interpreter.turtle.pen_mode = mode;
+            interpreter.turtle.pen_down = true;
+        });
+        Ok(())
+    }
+
+    fn visit_show_turtle(&mut self) -> Self::Result {
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.visible = true);
+        Ok(())
+    }
+
+    fn visit_hide_turtle(&mut self) -> Self::Result {
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.visible = false);
+        Ok(())
+    }
+
+    fn visit_stamp(&mut self) -> Self::Result {
+        // Stamp uses the same drawing as the sprite, but doesn't depend on visibility or the pen.
+        for turtle in self.turtles_to_stamp() {
+            canvas::draw_turtle(&turtle, &self.transform.to_canvas(&turtle.location()));
+        }
+        Ok(())
+    }
+
+    fn visit_push_turtle(&mut self) -> Self::Result {
+        self.for_each_active_turtle(|interpreter| {
+            let saved = interpreter.saved_turtles.entry(interpreter.current_turtle.clone()).or_default();
+            saved.push(interpreter.turtle.clone());
+        });
+        Ok(())
+    }
+
+    fn visit_pop_turtle(&mut self) -> Self::Result {
+        self.try_for_each_active_turtle(|interpreter| {
+            let saved = interpreter.saved_turtles.get_mut(&interpreter.current_turtle).and_then(|saved| saved.pop());
+            match saved {
+                Some(turtle) => {
+                    // Turtle jumps back without drawing.
+                    interpreter.record_path_segment(PathSegment::MoveTo(interpreter.transform.to_canvas(&turtle.location())));
+                    interpreter.turtle = turtle;
+                    Ok(())
+                },
+                None => Err(InterpreterError::Runtime {
+                    message: format!("POPTURTLE has no saved state for turtle {}. Use PUSHTURTLE first.", interpreter.current_turtle),
+                }),
+            }
+        })
+    }
+
+    fn visit_set_shape(&mut self, stmt: &SetShapeStmt) -> Self::Result {
+        // List allows path data with spaces, e.g. SETSHAPE [M 0 -10 L 6 6 L -6 6 Z].
+        let shape = print_form(&self.evaluate(&stmt.shape)?);
+        let shape = match TurtleShape::from_name(&shape) {
+            Some(shape) => shape,
+            // SVG path data always starts with a move.
+            None if shape.starts_with(['M', 'm']) => TurtleShape::Path(shape),
+            None => return Err(InterpreterError::Runtime {
+                message: format!("SETSHAPE expected turtle, triangle or SVG path data but got {}.", shape),
+            }),
+        };
+        self.for_each_active_turtle(|interpreter| interpreter.turtle.shape = shape.clone());
+        Ok(())
+    }
+
+    fn visit_set_boundary_mode(&mut self, mode: &BoundaryMode) -> Self::Result {
+        self.boundary_mode = *mode;
+        Ok(())
+    }
+
+    fn visit_tell(&mut self, stmt: &TellStmt) -> Self::Result {
+        let ids = self.evaluate_turtle_ids(&stmt.turtles, "TELL")?;
+        self.set_active_turtles(ids);
+        Ok(())
+    }
+
+    fn visit_ask(&mut self, stmt: &AskStmt) -> Self::Result {
+        let ids = self.evaluate_turtle_ids(&stmt.turtles, "ASK")?;
+        let told = self.active_turtles.clone();
+        let current = self.current_turtle.clone();
+        self.set_active_turtles(ids);
+        let result = stmt.body.accept(self);
+        self.active_turtles = told;
+        self.select_turtle(&current);
+        result
+    }
+
+    fn visit_stop(&mut self) -> Self::Result {
+        // STOP outside of a procedure stops the whole script.
+        self.return_value = Some(ProcedureExit::Stop);
+        Ok(())
+    }
+
+    fn visit_output(&mut self, stmt: &OutputStmt) -> Self::Result {
+        if self.call_depth == 0 {
+            return Err(InterpreterError::Runtime {
+                message: "OUTPUT can only be used inside a procedure.".into(),
+            });
+        }
+        let value = self.evaluate(&stmt.value)?;
+        self.return_value = Some(ProcedureExit::Output(value));
         Ok(())
     }
 }
@@ -211,10 +1079,548 @@ impl StmtVisitor for Interpreter {
 impl ExprVisitor for Interpreter {
     type Result = Result<Obj, InterpreterError>;
 
-    fn visit_literal(&mut self, literal: &mut Literal) -> Self::Result {
-        match literal {
-            Literal::Number(number) => Ok(Obj::Num(*number)),
-            Literal::Color(string) => Ok(Obj::Color(string.to_string())),
+    fn visit_literal(&mut self, literal: &Literal) -> Self::Result {
+        Ok(literal_to_obj(literal))
+    }
+
+    fn visit_variable(&mut self, expr: &VariableExpr) -> Self::Result {
+        match self.environment.get(&expr.name) {
+            Some(value) => Ok(value.clone()),
+            None => Err(InterpreterError::Runtime {
+                message: format!("Variable `:{}` has no value.", expr.name),
+            }),
+        }
+    }
+
+    fn visit_unary(&mut self, expr: &UnaryExpr) -> Self::Result {
+        match expr.operator {
+            UnaryOperator::Negate => {
+                let right = self.evaluate_number(&expr.right, "-")?;
+                Ok(Obj::Num(-right))
+            }
+            UnaryOperator::Not => {
+                let right = self.evaluate_bool(&expr.right, "NOT")?;
+                Ok(Obj::Bool(!right))
+            }
+        }
+    }
+
+    fn visit_binary(&mut self, expr: &BinaryExpr) -> Self::Result {
+        let symbol = match expr.operator {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Equal => "=",
+            BinaryOperator::NotEqual => "<>",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterEqual => ">=",
+        };
+        // Equality is defined for all values, the other operators only work with numbers.
+        match expr.operator {
+            BinaryOperator::Equal => {
+                let left = self.evaluate(&expr.left)?;
+                let right = self.evaluate(&expr.right)?;
+                return Ok(Obj::Bool(primitives::equal(&left, &right)));
+            }
+            BinaryOperator::NotEqual => {
+                let left = self.evaluate(&expr.left)?;
+                let right = self.evaluate(&expr.right)?;
+                return Ok(Obj::Bool(!primitives::equal(&left, &right)));
+            }
+            _ => {}
+        }
+        let left = self.evaluate_number(&expr.left, symbol)?;
+        let right = self.evaluate_number(&expr.right, symbol)?;
+        match expr.operator {
+            BinaryOperator::Add => Ok(Obj::Num(left + right)),
+            BinaryOperator::Subtract => Ok(Obj::Num(left - right)),
+            BinaryOperator::Multiply => Ok(Obj::Num(left * right)),
+            BinaryOperator::Divide if right == 0.0 => Err(InterpreterError::Runtime {
+                message: "Division by zero.".into(),
+            }),
+            BinaryOperator::Divide => Ok(Obj::Num(left / right)),
+            BinaryOperator::Less => Ok(Obj::Bool(left < right)),
+            BinaryOperator::LessEqual => Ok(Obj::Bool(left <= right)),
+            BinaryOperator::Greater => Ok(Obj::Bool(left > right)),
+            BinaryOperator::GreaterEqual => Ok(Obj::Bool(left >= right)),
+            BinaryOperator::Equal | BinaryOperator::NotEqual => unreachable!("Equality is evaluated above."),
+        }
+    }
+
+    fn visit_builtin_call(&mut self, expr: &BuiltinCallExpr) -> Self::Result {
+        let mut arguments = Vec::with_capacity(expr.arguments.len());
+        for argument in expr.arguments.iter() {
+            arguments.push(self.evaluate(argument)?);
         }
+        self.call_builtin(expr.builtin, arguments)
+    }
+
+    fn visit_procedure_call(&mut self, expr: &ProcedureCallExpr) -> Self::Result {
+        match self.call_procedure(&expr.name, &expr.arguments)? {
+            Some(value) => Ok(value),
+            None => Err(InterpreterError::Runtime {
+                message: format!("Procedure `{}` did not OUTPUT a value.", expr.name),
+            }),
+        }
+    }
+
+    fn visit_logical(&mut self, expr: &LogicalExpr) -> Self::Result {
+        // Right side is evaluated only when needed.
+        let left = self.evaluate_bool(&expr.left, "AND/OR")?;
+        let value = match expr.operator {
+            LogicalOperator::And => left && self.evaluate_bool(&expr.right, "AND")?,
+            LogicalOperator::Or => left || self.evaluate_bool(&expr.right, "OR")?,
+        };
+        Ok(Obj::Bool(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::domain::color::Color;
+    use crate::domain::config::{RECURSION_DEPTH_CEILING, WASM_STACK_SIZE};
+    use crate::domain::errors::InterpreterError;
+    use crate::domain::geometry::{Location2d, PathSegment, Size2d};
+    use crate::domain::interpreter::interpreter::{Interpreter, Obj, FIRST_TURTLE};
+    use crate::domain::parser::parser::Parser;
+    use crate::domain::turtle::{LineCap, LineJoin, PenMode, Turtle, TurtleShape};
+
+    fn new_interpreter() -> Interpreter {
+        let canvas_size = Size2d { width: 365.0, height: 365.0 };
+        Interpreter::new(Turtle::default(), canvas_size)
+    }
+
+    fn interpret(code: &str) -> Interpreter {
+        let mut interpreter = new_interpreter();
+        let statements = Parser::new_from_str(code).parse().unwrap();
+        interpreter.interpret_statements(&statements).unwrap();
+        interpreter
+    }
+
+    #[test]
+    fn make_assigns_global_variable() {
+        let interpreter = interpret("MAKE \"size 50 MAKE \"copy :SIZE");
+        assert_eq!(interpreter.environment.get("copy"), Some(&Obj::Num(50.0)));
+    }
+
+    #[test]
+    fn arithmetic_follows_operator_precedence() {
+        let interpreter = interpret("MAKE \"a 10 * 3 + 5 MAKE \"b (2 + 3) * -2 MAKE \"c 360 / 8 - 1");
+        assert_eq!(interpreter.environment.get("a"), Some(&Obj::Num(35.0)));
+        assert_eq!(interpreter.environment.get("b"), Some(&Obj::Num(-10.0)));
+        assert_eq!(interpreter.environment.get("c"), Some(&Obj::Num(44.0)));
+    }
+
+    #[test]
+    fn non_finite_words_are_not_numbers() {
+        for code in ["PRINT \"nan + 1", "PRINT \"inf * 2", "FD \"infinity"] {
+            let mut interpreter = new_interpreter();
+            let statements = Parser::new_from_str(code).parse().unwrap();
+            assert!(interpreter.interpret_statements(&statements).is_err(), "{}", code);
+        }
+        let interpreter = interpret("MAKE \"same \"NaN = \"nan");
+        assert_eq!(interpreter.environment.get("same"), Some(&Obj::Bool(true)));
+    }
+
+    #[test]
+    fn ifelse_picks_branch_by_condition() {
+        let interpreter = interpret("MAKE \"size 60 IFELSE AND :size > 50 NOT :size = 70 [ MAKE \"big 1 ] [ MAKE \"big 0 ] IF :size <= 50 [ MAKE \"small 1 ]");
+        assert_eq!(interpreter.environment.get("big"), Some(&Obj::Num(1.0)));
+        assert_eq!(interpreter.environment.get("small"), None);
+    }
+
+    #[test]
+    fn loops_expose_loop_index() {
+        let interpreter = interpret("MAKE \"sum 0 FOR [i 1 9 2] [ MAKE \"sum :sum + :i ] MAKE \"n 0 REPEAT 3 [ MAKE \"n :n + REPCOUNT ]");
+        assert_eq!(interpreter.environment.get("sum"), Some(&Obj::Num(25.0)));
+        assert_eq!(interpreter.environment.get("n"), Some(&Obj::Num(6.0)));
+        assert_eq!(interpreter.environment.get("i"), None);
+    }
+
+    #[test]
+    fn while_and_until_loops() {
+        let interpreter = interpret("MAKE \"a 0 WHILE [:a < 5] [ MAKE \"a :a + 1 ] MAKE \"b 0 UNTIL [:b >= 3] [ MAKE \"b :b + 1 ]");
+        assert_eq!(interpreter.environment.get("a"), Some(&Obj::Num(5.0)));
+        assert_eq!(interpreter.environment.get("b"), Some(&Obj::Num(3.0)));
+    }
+
+    #[test]
+    fn for_loop_counts_steps_without_drifting() {
+        let interpreter = interpret("MAKE \"n 0 FOR [i 0 1 0.1] [ MAKE \"n :n + 1 MAKE \"last :i ]");
+        assert_eq!(interpreter.environment.get("n"), Some(&Obj::Num(11.0)));
+        assert_eq!(interpreter.environment.get("last"), Some(&Obj::Num(1.0)));
+    }
+
+    #[test]
+    fn every_loop_stops_after_too_many_iterations() {
+        let codes = [
+            "REPEAT 1000000 [ ]",
+            "FOREVER [ ]",
+            "WHILE [\"true] [ ]",
+            "FOR [i 1 1000000] [ ]",
+            "FOR [i 1 POWER 10 400] [ ]",
+        ];
+        for code in codes {
+            let mut interpreter = new_interpreter();
+            let statements = Parser::new_from_str(code).parse().unwrap();
+            assert!(interpreter.interpret_statements(&statements).is_err(), "{}", code);
+        }
+        let mut interpreter = new_interpreter();
+        let statements = Parser::new_from_str("MAKE \"n 0 FOREVER [ MAKE \"n :n + 1 ]").parse().unwrap();
+        assert!(interpreter.interpret_statements(&statements).is_err());
+        assert_eq!(interpreter.environment.get("n"), Some(&Obj::Num(100000.0)));
+    }
+
+    #[test]
+    fn value_in_place_of_command_is_an_error() {
+        let codes = ["5", "PU FD 10 20", "[1 2]", "\"word", "TO double :n OUTPUT :n * 2 END double 5"];
+        for code in codes {
+            let mut interpreter = new_interpreter();
+            let statements = Parser::new_from_str(code).parse().unwrap();
+            let result = interpreter.interpret_statements(&statements);
+            assert!(matches!(result, Err(InterpreterError::Runtime { message }) if message.starts_with("You don't say what to do with")), "{}", code);
+        }
+    }
+
+    #[test]
+    fn recursive_procedure_outputs_value() {
+        let interpreter = interpret("TO factorial :n IF :n <= 1 [ OUTPUT 1 ] OUTPUT :n * factorial :n - 1 END MAKE \"result factorial 5");
+        assert_eq!(interpreter.environment.get("result"), Some(&Obj::Num(120.0)));
+    }
+
+    #[test]
+    fn stop_ends_procedure_early() {
+        let interpreter = interpret("TO countdown :n IF :n = 0 [ STOP ] MAKE \"calls :calls + 1 countdown :n - 1 END MAKE \"calls 0 countdown 4 MAKE \"after 1");
+        assert_eq!(interpreter.environment.get("calls"), Some(&Obj::Num(4.0)));
+        assert_eq!(interpreter.environment.get("after"), Some(&Obj::Num(1.0)));
+    }
+
+    #[test]
+    fn recursion_deeper_than_limit_is_an_error() {
+        let mut interpreter = new_interpreter();
+        interpreter.max_recursion_depth = 10;
+        let statements = Parser::new_from_str("TO loop :n loop :n + 1 END loop 0").parse().unwrap();
+        assert!(interpreter.interpret_statements(&statements).is_err());
+    }
+
+    #[test]
+    fn recursion_limit_fits_in_wasm_stack() {
+        // Native frames are bigger than WASM ones, so a native thread with the WASM stack size is a safe stand-in.
+        let code = format!(
+            "SETRECURSIONDEPTH {} TO nest :n IF :n > 0 [ REPEAT 1 [ FOR [i 1 1] [ nest :n + 1 ] ] ] END nest 1",
+            RECURSION_DEPTH_CEILING,
+        );
+        let result = std::thread::Builder::new()
+            .stack_size(WASM_STACK_SIZE)
+            .spawn(move || {
+                let mut interpreter = new_interpreter();
+                let statements = Parser::new_from_str(&code).parse().unwrap();
+                interpreter.interpret_statements(&statements)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        match result {
+            Err(InterpreterError::Runtime { message }) => assert!(message.contains(&format!("Maximum depth is {}", RECURSION_DEPTH_CEILING)), "{}", message),
+            other => panic!("Expected the recursion limit error but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_recursion_depth_is_an_error() {
+        let interpreter = interpret("SETRECURSIONDEPTH 20");
+        assert_eq!(interpreter.max_recursion_depth, 20);
+        for code in ["SETRECURSIONDEPTH 0", "SETRECURSIONDEPTH 2.5", "SETRECURSIONDEPTH 100000"] {
+            let mut interpreter = new_interpreter();
+            let statements = Parser::new_from_str(code).parse().unwrap();
+            assert!(interpreter.interpret_statements(&statements).is_err(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn recursion_within_default_limit() {
+        let interpreter = interpret("TO down :n IF :n = 0 [ STOP ] down :n - 1 END down 250");
+        assert_eq!(interpreter.call_depth, 0);
+    }
+
+    #[test]
+    fn list_primitives() {
+        let interpreter = interpret("MAKE \"sides [10 20 30] MAKE \"second ITEM 2 :sides MAKE \"rest COUNT BF :sides MAKE \"more LPUT 40 :sides MAKE \"nested SE [1 2] LIST 3 [4]");
+        assert_eq!(interpreter.environment.get("second"), Some(&Obj::Num(20.0)));
+        assert_eq!(interpreter.environment.get("rest"), Some(&Obj::Num(2.0)));
+        assert_eq!(interpreter.environment.get("more").unwrap().to_string(), "[10 20 30 40]");
+        assert_eq!(interpreter.environment.get("nested").unwrap().to_string(), "[1 2 3 [4]]");
+    }
+
+    #[test]
+    fn word_primitives() {
+        let interpreter = interpret("MAKE \"w WORD \"tur \"tle MAKE \"f FIRST :w MAKE \"bl BL :w MAKE \"same \"TURTLE = :w MAKE \"empty EMPTYP BF \"a MAKE \"p (WORD \"a \"b)");
+        assert_eq!(interpreter.environment.get("w"), Some(&Obj::Word("turtle".into())));
+        assert_eq!(interpreter.environment.get("f"), Some(&Obj::Word("t".into())));
+        assert_eq!(interpreter.environment.get("bl"), Some(&Obj::Word("turtl".into())));
+        assert_eq!(interpreter.environment.get("same"), Some(&Obj::Bool(true)));
+        assert_eq!(interpreter.environment.get("empty"), Some(&Obj::Bool(true)));
+        assert_eq!(interpreter.environment.get("p"), Some(&Obj::Word("ab".into())));
+    }
+
+    #[test]
+    fn math_primitives() {
+        let interpreter = interpret("MAKE \"a ROUND (SIN 30) * 10 MAKE \"b SQRT POWER 3 2 MAKE \"c MODULO -7 3 MAKE \"d REMAINDER -7 3 MAKE \"e INT ARCTAN 1");
+        assert_eq!(interpreter.environment.get("a"), Some(&Obj::Num(5.0)));
+        assert_eq!(interpreter.environment.get("b"), Some(&Obj::Num(3.0)));
+        assert_eq!(interpreter.environment.get("c"), Some(&Obj::Num(2.0)));
+        assert_eq!(interpreter.environment.get("d"), Some(&Obj::Num(-1.0)));
+        assert_eq!(interpreter.environment.get("e"), Some(&Obj::Num(45.0)));
+    }
+
+    #[test]
+    fn rerandom_makes_random_reproducible() {
+        let code = "RERANDOM 7 MAKE \"first (LIST RANDOM 100 RANDOM 100) RERANDOM 7 MAKE \"second (LIST RANDOM 100 RANDOM 100)";
+        let interpreter = interpret(code);
+        assert_eq!(interpreter.environment.get("first"), interpreter.environment.get("second"));
+    }
+
+    #[test]
+    fn print_show_and_type_write_to_output() {
+        let interpreter = interpret("PRINT [a [b c]] SHOW [a [b c]] TYPE \"x TYPE 1 + 2 PRINT \"!");
+        assert_eq!(interpreter.output(), "a [b c]\n[a [b c]]\nx3!\n");
+    }
+
+    #[test]
+    fn pen_up_moves_without_drawing() {
+        // Pen is up so the turtle never touches the canvas.
+        let interpreter = interpret("PU MAKE \"up PENDOWNP FD 10 RT 90 BK 20");
+        assert_eq!(interpreter.environment.get("up"), Some(&Obj::Bool(false)));
+        assert_eq!(interpreter.turtle.location(), Location2d { x: -20.0, y: 10.0 });
+    }
+
+    #[test]
+    fn pen_modes_put_the_pen_down() {
+        let interpreter = interpret("PU PENERASE MAKE \"erase PENDOWNP PU PENREVERSE");
+        assert_eq!(interpreter.environment.get("erase"), Some(&Obj::Bool(true)));
+        assert!(interpreter.turtle.pen_down);
+        assert_eq!(interpreter.turtle.pen_mode, PenMode::Reverse);
+    }
+
+    #[test]
+    fn line_style_is_stored_on_turtle() {
+        let interpreter = interpret("SETPENSIZE 2 + 1 SETLINECAP \"round SETLINEJOIN \"Bevel SETDASH [5 3]");
+        assert_eq!(interpreter.turtle.pen_size, 3.0);
+        assert_eq!(interpreter.turtle.line_cap, LineCap::Round);
+        assert_eq!(interpreter.turtle.line_join, LineJoin::Bevel);
+        assert_eq!(interpreter.turtle.dash, vec![5.0, 3.0]);
+    }
+
+    #[test]
+    fn invalid_line_style_is_an_error() {
+        for code in ["SETPENSIZE -1", "SETLINECAP \"dotted", "SETDASH 5", "SETDASH [5 -3]"] {
+            let mut interpreter = new_interpreter();
+            let statements = Parser::new_from_str(code).parse().unwrap();
+            assert!(interpreter.interpret_statements(&statements).is_err(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn absolute_positioning() {
+        let interpreter = interpret("PU SETXY 10 20 SETX 30 SETHEADING 90 FD 5");
+        assert_eq!(interpreter.turtle.location(), Location2d { x: 35.0, y: 20.0 });
+        let interpreter = interpret("PU SETPOS [40 50] SETY 60 SETH 180 FD 10");
+        assert_eq!(interpreter.turtle.location(), Location2d { x: 40.0, y: 50.0 });
+        let interpreter = interpret("PU SETPOS [40 50] RT 45 HOME");
+        assert_eq!(interpreter.turtle.location(), Location2d { x: 0.0, y: 0.0 });
+        assert_eq!(interpreter.turtle.heading, 0.0);
+    }
+
+    #[test]
+    fn turtle_state_queries() {
+        let code = "PU SETXY 10 20 LT 90 SETPENSIZE 3 \
+            MAKE \"x XCOR MAKE \"pos POS MAKE \"heading HEADING MAKE \"size PENSIZE MAKE \"color PENCOLOR \
+            MAKE \"towards TOWARDS [10 50] MAKE \"distance DISTANCEXY 13 24";
+        let interpreter = interpret(code);
+        assert_eq!(interpreter.environment.get("x"), Some(&Obj::Num(10.0)));
+        assert_eq!(interpreter.environment.get("pos"), Some(&Obj::List(vec![Obj::Num(10.0), Obj::Num(20.0)])));
+        assert_eq!(interpreter.environment.get("heading"), Some(&Obj::Num(270.0)));
+        assert_eq!(interpreter.environment.get("size"), Some(&Obj::Num(3.0)));
+        assert_eq!(interpreter.environment.get("color"), Some(&Obj::Color(Color::BLACK)));
+        // Y grows upwards, so a point above the turtle is north.
+        assert_eq!(interpreter.environment.get("towards"), Some(&Obj::Num(0.0)));
+        assert_eq!(interpreter.environment.get("distance"), Some(&Obj::Num(5.0)));
+    }
+
+    #[test]
+    fn logo_coordinates_and_headings() {
+        let interpreter = interpret("PU RT 90 FD 100 LT 90 FD 50 LT 90 MAKE \"pos POS MAKE \"heading HEADING");
+        assert_eq!(interpreter.environment.get("pos"), Some(&Obj::List(vec![Obj::Num(100.0), Obj::Num(50.0)])));
+        assert_eq!(interpreter.environment.get("heading"), Some(&Obj::Num(270.0)));
+    }
+
+    #[test]
+    fn label_style_is_stored_on_turtle() {
+        let interpreter = interpret("SETLABELHEIGHT 20 SETFONT [Times New Roman]");
+        assert_eq!(interpreter.turtle.label_height, 20.0);
+        assert_eq!(interpreter.turtle.font, "Times New Roman");
+    }
+
+    #[test]
+    fn colors_from_names_lists_hsv_and_palette() {
+        let interpreter = interpret("COLOR \"Red MAKE \"name PENCOLOR \
+            SETPC [0 128 255] MAKE \"rgb PENCOLOR \
+            SETPENCOLOR [0 0 0 128] MAKE \"alpha PENCOLOR \
+            COLOR HSV 120 100 100 MAKE \"hsv PENCOLOR \
+            COLOR 14 MAKE \"palette PENCOLOR \
+            SETPALETTE 14 #abc COLOR 14 MAKE \"changed PENCOLOR \
+            COLOR #ff000080 MAKE \"hex PENCOLOR");
+        let color = |name: &str| interpreter.environment.get(name).unwrap().to_string();
+        assert_eq!(color("name"), "#ff0000");
+        assert_eq!(color("rgb"), "#0080ff");
+        assert_eq!(color("alpha"), "#00000080");
+        assert_eq!(color("hsv"), "#00ff00");
+        assert_eq!(color("palette"), "#ffa300");
+        assert_eq!(color("changed"), "#aabbcc");
+        assert_eq!(color("hex"), "#ff000080");
+    }
+
+    #[test]
+    fn invalid_colors_are_an_error() {
+        for code in ["COLOR \"reddish", "COLOR 16", "COLOR 1.5", "COLOR [0 0 256]", "COLOR [0 0]", "SETPALETTE 16 \"red"] {
+            let mut interpreter = new_interpreter();
+            let statements = Parser::new_from_str(code).parse().unwrap();
+            assert!(interpreter.interpret_statements(&statements).is_err(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn told_turtles_move_in_lockstep() {
+        let mut interpreter = interpret("PU TELL [1 2] PU RT 90 FD 10 ASK 2 [ FD 5 MAKE \"asked WHO ] MAKE \"told WHO");
+        assert_eq!(interpreter.environment.get("asked"), Some(&Obj::Num(2.0)));
+        assert_eq!(interpreter.environment.get("told"), Some(&Obj::List(vec![Obj::Num(1.0), Obj::Num(2.0)])));
+        assert_eq!(interpreter.turtle.location(), Location2d { x: 10.0, y: 0.0 });
+        interpreter.select_turtle("2");
+        assert_eq!(interpreter.turtle.location(), Location2d { x: 15.0, y: 0.0 });
+        interpreter.select_turtle("0");
+        assert_eq!(interpreter.turtle.location(), Location2d { x: 0.0, y: 0.0 });
+        assert_eq!(interpreter.turtle.heading, 0.0);
+    }
+
+    #[test]
+    fn each_turtle_traces_its_own_filled_path() {
+        let mut interpreter = interpret("TELL [1 2] PU ASK 2 [ RT 90 ]");
+        // Same as inside a FILLED block, which can't be filled without a canvas.
+        interpreter.filled_paths.push(BTreeMap::new());
+        let statements = Parser::new_from_str("FD 10").parse().unwrap();
+        interpreter.interpret_statements(&statements).unwrap();
+        let at = |x: f64, y: f64| interpreter.transform.to_canvas(&Location2d { x, y });
+        let paths = interpreter.filled_paths.pop().unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths["1"], vec![PathSegment::MoveTo(at(0.0, 0.0)), PathSegment::LineTo(at(0.0, 10.0))]);
+        assert_eq!(paths["2"], vec![PathSegment::MoveTo(at(0.0, 0.0)), PathSegment::LineTo(at(10.0, 0.0))]);
+    }
+
+    #[test]
+    fn arc_is_a_separate_piece_of_filled_path() {
+        let mut interpreter = interpret("PU");
+        interpreter.filled_paths.push(BTreeMap::new());
+        let statements = Parser::new_from_str("ARC 90 10 FD 20").parse().unwrap();
+        interpreter.interpret_statements(&statements).unwrap();
+        let at = |x: f64, y: f64| interpreter.transform.to_canvas(&Location2d { x, y });
+        let paths = interpreter.filled_paths.pop().unwrap();
+        assert_eq!(
+            paths[FIRST_TURTLE],
+            vec![
+                PathSegment::MoveTo(at(0.0, 0.0)),
+                PathSegment::MoveTo(at(0.0, 10.0)),
+                PathSegment::Arc {
+                    centre: at(0.0, 0.0),
+                    radius: 10.0,
+                    start_angle: interpreter.transform.to_canvas_angle(0.0),
+                    end_angle: interpreter.transform.to_canvas_angle(90.0),
+                    anticlockwise: false,
+                },
+                PathSegment::MoveTo(at(0.0, 0.0)),
+                PathSegment::LineTo(at(0.0, 20.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn boundary_modes_limit_the_turtle() {
+        let interpreter = interpret("PU WRAP RT 90 FD 200 LT 90 FD 400");
+        assert_eq!(interpreter.turtle.location(), Location2d { x: -165.0, y: 35.0 });
+        let interpreter = interpret("PU WINDOW FD 400");
+        assert_eq!(interpreter.turtle.location(), Location2d { x: 0.0, y: 400.0 });
+
+        let mut interpreter = new_interpreter();
+        let statements = Parser::new_from_str("PU FENCE FD 100 FD 100").parse().unwrap();
+        assert!(interpreter.interpret_statements(&statements).is_err());
+        assert_eq!(interpreter.turtle.location(), Location2d { x: 0.0, y: 100.0 });
+    }
+
+    #[test]
+    fn turtle_cannot_move_infinitely_far() {
+        let interpreter = interpret("PU WRAP FD 100000000000000");
+        assert!(interpreter.transform.contains(&interpreter.turtle.location()));
+        for code in ["PU WRAP FD POWER 10 400", "PU FD POWER 10 400", "PU SETXY 0 POWER 10 400"] {
+            let mut interpreter = new_interpreter();
+            let statements = Parser::new_from_str(code).parse().unwrap();
+            assert!(interpreter.interpret_statements(&statements).is_err(), "{}", code);
+            assert_eq!(interpreter.turtle.location(), Location2d { x: 0.0, y: 0.0 }, "{}", code);
+        }
+    }
+
+    #[test]
+    fn turtle_shape_and_visibility() {
+        let interpreter = interpret("HT SETSHAPE \"triangle");
+        assert!(!interpreter.turtle.visible);
+        assert_eq!(interpreter.turtle.shape, TurtleShape::Triangle);
+        let interpreter = interpret("SETSHAPE [M 0 -10 L 6 6 L -6 6 Z] ST");
+        assert!(interpreter.turtle.visible);
+        assert_eq!(interpreter.turtle.shape, TurtleShape::Path("M 0 -10 L 6 6 L -6 6 Z".into()));
+        let mut interpreter = new_interpreter();
+        let statements = Parser::new_from_str("SETSHAPE \"square").parse().unwrap();
+        assert!(interpreter.interpret_statements(&statements).is_err());
+    }
+
+    #[test]
+    fn hidden_turtles_with_pen_up_are_stamped() {
+        let mut interpreter = interpret("TELL [0 1] HT PU ASK 1 [ FD 20 ]");
+        let stamped: Vec<(Location2d, bool, bool)> = interpreter.turtles_to_stamp().iter()
+            .map(|turtle| (turtle.location(), turtle.visible, turtle.pen_down))
+            .collect();
+        assert_eq!(stamped, vec![
+            (Location2d { x: 0.0, y: 0.0 }, false, false),
+            (Location2d { x: 0.0, y: 20.0 }, false, false),
+        ]);
+    }
+
+    #[test]
+    fn popturtle_restores_pushed_turtle() {
+        let interpreter = interpret("PU RT 45 PUSHTURTLE FD 50 COLOR \"red PD SAVESTATE SETPENSIZE 5 RESTORESTATE POPTURTLE");
+        let expected = Turtle { heading: 45.0, pen_down: false, ..Turtle::default() };
+        assert!(interpreter.turtle == expected);
+
+        let mut interpreter = new_interpreter();
+        let statements = Parser::new_from_str("PUSHTURTLE POPTURTLE POPTURTLE").parse().unwrap();
+        assert!(interpreter.interpret_statements(&statements).is_err());
+    }
+
+    #[test]
+    fn invalid_turtles_are_an_error() {
+        for code in ["TELL []", "TELL -1", "TELL 1.5", "ASK [] [ FD 10 ]"] {
+            let mut interpreter = new_interpreter();
+            let statements = Parser::new_from_str(code).parse().unwrap();
+            assert!(interpreter.interpret_statements(&statements).is_err(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn make_inside_procedure_updates_global_variable() {
+        let interpreter = interpret("MAKE \"size 1 TO grow :by MAKE \"size :by END grow 7");
+        assert_eq!(interpreter.environment.get("size"), Some(&Obj::Num(7.0)));
+        assert_eq!(interpreter.environment.get("by"), None);
     }
 }
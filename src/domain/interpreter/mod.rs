@@ -1,2 +1,5 @@
+pub mod canvas;
+pub mod environment;
 pub mod interpreter;
+pub mod primitives;
 pub mod visitor;
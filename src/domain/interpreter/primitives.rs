@@ -0,0 +1,213 @@
+use crate::domain::errors::InterpreterError;
+use crate::domain::interpreter::interpreter::Obj;
+
+// Primitives that operate on Logo words, lists and numbers.
+// Numbers are words too, so `FIRST 123` outputs `1`.
+
+fn error<T>(message: String) -> Result<T, InterpreterError> {
+    Err(InterpreterError::Runtime { message })
+}
+
+fn chars_of(thing: &Obj) -> Vec<char> {
+    thing.to_string().chars().collect()
+}
+
+fn word_of(chars: &[char]) -> Obj {
+    Obj::Word(chars.iter().collect())
+}
+
+pub fn first(thing: &Obj) -> Result<Obj, InterpreterError> {
+    match thing {
+        Obj::List(items) => match items.first() {
+            Some(item) => Ok(item.clone()),
+            None => error("FIRST doesn't like an empty list as input.".into()),
+        },
+        word => match chars_of(word).first() {
+            Some(char) => Ok(Obj::Word(char.to_string())),
+            None => error("FIRST doesn't like an empty word as input.".into()),
+        },
+    }
+}
+
+pub fn butfirst(thing: &Obj) -> Result<Obj, InterpreterError> {
+    match thing {
+        Obj::List(items) if items.is_empty() => error("BUTFIRST doesn't like an empty list as input.".into()),
+        Obj::List(items) => Ok(Obj::List(items[1..].to_vec())),
+        word => match chars_of(word).as_slice() {
+            [] => error("BUTFIRST doesn't like an empty word as input.".into()),
+            [_, rest @ ..] => Ok(word_of(rest)),
+        },
+    }
+}
+
+pub fn last(thing: &Obj) -> Result<Obj, InterpreterError> {
+    match thing {
+        Obj::List(items) => match items.last() {
+            Some(item) => Ok(item.clone()),
+            None => error("LAST doesn't like an empty list as input.".into()),
+        },
+        word => match chars_of(word).last() {
+            Some(char) => Ok(Obj::Word(char.to_string())),
+            None => error("LAST doesn't like an empty word as input.".into()),
+        },
+    }
+}
+
+pub fn butlast(thing: &Obj) -> Result<Obj, InterpreterError> {
+    match thing {
+        Obj::List(items) if items.is_empty() => error("BUTLAST doesn't like an empty list as input.".into()),
+        Obj::List(items) => Ok(Obj::List(items[..items.len() - 1].to_vec())),
+        word => match chars_of(word).as_slice() {
+            [] => error("BUTLAST doesn't like an empty word as input.".into()),
+            [rest @ .., _] => Ok(word_of(rest)),
+        },
+    }
+}
+
+pub fn item(index: f64, thing: &Obj) -> Result<Obj, InterpreterError> {
+    // Items are indexed from 1.
+    let position = index.round() as usize;
+    let found = match thing {
+        Obj::List(items) => position.checked_sub(1).and_then(|x| items.get(x)).cloned(),
+        word => position.checked_sub(1).and_then(|x| chars_of(word).get(x).map(|char| Obj::Word(char.to_string()))),
+    };
+    match found {
+        Some(item) => Ok(item),
+        None => error(format!("ITEM doesn't like {} as input, there is no such item in {}.", index, thing)),
+    }
+}
+
+pub fn fput(thing: &Obj, list: &Obj) -> Result<Obj, InterpreterError> {
+    match list {
+        Obj::List(items) => {
+            let mut result = vec![thing.clone()];
+            result.extend(items.iter().cloned());
+            Ok(Obj::List(result))
+        },
+        other => error(format!("FPUT expected a list but got {}.", other)),
+    }
+}
+
+pub fn lput(thing: &Obj, list: &Obj) -> Result<Obj, InterpreterError> {
+    match list {
+        Obj::List(items) => {
+            let mut result = items.clone();
+            result.push(thing.clone());
+            Ok(Obj::List(result))
+        },
+        other => error(format!("LPUT expected a list but got {}.", other)),
+    }
+}
+
+pub fn count(thing: &Obj) -> Obj {
+    match thing {
+        Obj::List(items) => Obj::Num(items.len() as f64),
+        word => Obj::Num(chars_of(word).len() as f64),
+    }
+}
+
+pub fn emptyp(thing: &Obj) -> Obj {
+    match thing {
+        Obj::List(items) => Obj::Bool(items.is_empty()),
+        word => Obj::Bool(chars_of(word).is_empty()),
+    }
+}
+
+pub fn sentence(first: &Obj, second: &Obj) -> Obj {
+    // Lists are flattened by one level, words become members of the sentence.
+    let mut result = Vec::new();
+    for thing in [first, second] {
+        match thing {
+            Obj::List(items) => result.extend(items.iter().cloned()),
+            word => result.push(word.clone()),
+        }
+    }
+    Obj::List(result)
+}
+
+pub fn word(first: &Obj, second: &Obj) -> Result<Obj, InterpreterError> {
+    match (first, second) {
+        (Obj::List(_), _) | (_, Obj::List(_)) => error("WORD doesn't like a list as input.".into()),
+        (first, second) => Ok(Obj::Word(format!("{}{}", first, second))),
+    }
+}
+
+pub fn list(first: &Obj, second: &Obj) -> Obj {
+    Obj::List(vec![first.clone(), second.clone()])
+}
+
+pub fn equal(first: &Obj, second: &Obj) -> bool {
+    // Words are compared case-insensitively and `"10` is equal to `10`, same as in UCBLogo.
+    match (first, second) {
+        (Obj::List(first), Obj::List(second)) =>
+            first.len() == second.len()
+                && first.iter().zip(second.iter()).all(|(x, y)| equal(x, y)),
+        (Obj::List(_), _) | (_, Obj::List(_)) => false,
+        (first, second) => match (as_number(first), as_number(second)) {
+            (Some(x), Some(y)) => x == y,
+            _ => first.to_string().eq_ignore_ascii_case(&second.to_string()),
+        },
+    }
+}
+
+pub fn as_number(thing: &Obj) -> Option<f64> {
+    match thing {
+        Obj::Num(number) => Some(*number),
+        // Rust also parses words like `inf` and `nan`. In Logo they are just words.
+        Obj::Word(word) => word.parse::<f64>().ok().filter(|x| x.is_finite()),
+        _ => None,
+    }
+}
+
+// Trigonometric primitives work with degrees, like everything else in Logo.
+
+pub fn sin(degrees: f64) -> Obj {
+    Obj::Num(degrees.to_radians().sin())
+}
+
+pub fn cos(degrees: f64) -> Obj {
+    Obj::Num(degrees.to_radians().cos())
+}
+
+pub fn tan(degrees: f64) -> Obj {
+    Obj::Num(degrees.to_radians().tan())
+}
+
+pub fn arctan(number: f64) -> Obj {
+    Obj::Num(number.atan().to_degrees())
+}
+
+pub fn sqrt(number: f64) -> Result<Obj, InterpreterError> {
+    if number < 0.0 {
+        return error(format!("SQRT doesn't like {} as input.", number));
+    }
+    Ok(Obj::Num(number.sqrt()))
+}
+
+pub fn ln(number: f64) -> Result<Obj, InterpreterError> {
+    if number <= 0.0 {
+        return error(format!("LN doesn't like {} as input.", number));
+    }
+    Ok(Obj::Num(number.ln()))
+}
+
+pub fn remainder(dividend: f64, divisor: f64) -> Result<Obj, InterpreterError> {
+    // Result has the sign of the dividend.
+    if divisor == 0.0 {
+        return error("REMAINDER doesn't like 0 as divisor.".into());
+    }
+    Ok(Obj::Num(dividend % divisor))
+}
+
+pub fn modulo(dividend: f64, divisor: f64) -> Result<Obj, InterpreterError> {
+    // Result has the sign of the divisor.
+    if divisor == 0.0 {
+        return error("MODULO doesn't like 0 as divisor.".into());
+    }
+    let remainder = dividend % divisor;
+    if remainder != 0.0 && (remainder < 0.0) != (divisor < 0.0) {
+        Ok(Obj::Num(remainder + divisor))
+    } else {
+        Ok(Obj::Num(remainder))
+    }
+}
@@ -1,31 +1,81 @@
 use crate::domain::parser::expressions::*;
 use crate::domain::parser::statements::*;
+use crate::domain::geometry::BoundaryMode;
+use crate::domain::turtle::PenMode;
 
 
 pub trait ExprVisitor {
     type Result;
 
-    fn visit_literal(&mut self, expr: &mut Literal) -> Self::Result;
+    fn visit_literal(&mut self, expr: &Literal) -> Self::Result;
+    fn visit_variable(&mut self, expr: &VariableExpr) -> Self::Result;
+    fn visit_unary(&mut self, expr: &UnaryExpr) -> Self::Result;
+    fn visit_binary(&mut self, expr: &BinaryExpr) -> Self::Result;
+    fn visit_logical(&mut self, expr: &LogicalExpr) -> Self::Result;
+    fn visit_builtin_call(&mut self, expr: &BuiltinCallExpr) -> Self::Result;
+    fn visit_procedure_call(&mut self, expr: &ProcedureCallExpr) -> Self::Result;
 }
 pub trait StmtVisitor {
     type Result;
 
-    fn visit_block(&mut self, stmt: &mut Vec<Stmt>) -> Self::Result;
-    fn visit_expression(&mut self, stmt: &mut Expr) -> Self::Result;
-    fn visit_repeat(&mut self, repeat_stmt: &mut RepeatStmt) -> Self::Result;
-    fn visit_set_color(&mut self, stmt: &mut SetColorStmt) -> Self::Result;
-    fn visit_move_forward(&mut self, stmt: &mut MoveForwardStmt) -> Self::Result;
-    fn visit_move_backward(&mut self, stmt: &mut MoveBackwardStmt) -> Self::Result;
-    fn visit_rotate_right(&mut self, stmt: &mut RotateRightStmt) -> Self::Result;
-    fn visit_rotate_left(&mut self, stmt: &mut RotateLeftStmt) -> Self::Result;
+    fn visit_block(&mut self, stmt: &[Stmt]) -> Self::Result;
+    fn visit_expression(&mut self, stmt: &Expr) -> Self::Result;
+    fn visit_repeat(&mut self, repeat_stmt: &RepeatStmt) -> Self::Result;
+    fn visit_while(&mut self, stmt: &WhileStmt) -> Self::Result;
+    fn visit_forever(&mut self, stmt: &ForeverStmt) -> Self::Result;
+    fn visit_for(&mut self, stmt: &ForStmt) -> Self::Result;
+    fn visit_if(&mut self, stmt: &IfStmt) -> Self::Result;
+    fn visit_set_color(&mut self, stmt: &SetColorStmt) -> Self::Result;
+    fn visit_set_palette(&mut self, stmt: &SetPaletteStmt) -> Self::Result;
+    fn visit_set_background(&mut self, stmt: &SetBackgroundStmt) -> Self::Result;
+    fn visit_set_pen_size(&mut self, stmt: &SetPenSizeStmt) -> Self::Result;
+    fn visit_set_line_cap(&mut self, stmt: &SetLineCapStmt) -> Self::Result;
+    fn visit_set_line_join(&mut self, stmt: &SetLineJoinStmt) -> Self::Result;
+    fn visit_set_dash(&mut self, stmt: &SetDashStmt) -> Self::Result;
+    fn visit_move_forward(&mut self, stmt: &MoveForwardStmt) -> Self::Result;
+    fn visit_move_backward(&mut self, stmt: &MoveBackwardStmt) -> Self::Result;
+    fn visit_rotate_right(&mut self, stmt: &RotateRightStmt) -> Self::Result;
+    fn visit_rotate_left(&mut self, stmt: &RotateLeftStmt) -> Self::Result;
+    fn visit_arc(&mut self, stmt: &ArcStmt) -> Self::Result;
+    fn visit_filled(&mut self, stmt: &FilledStmt) -> Self::Result;
+    fn visit_fill(&mut self) -> Self::Result;
+    fn visit_label(&mut self, stmt: &LabelStmt) -> Self::Result;
+    fn visit_set_label_height(&mut self, stmt: &SetLabelHeightStmt) -> Self::Result;
+    fn visit_set_font(&mut self, stmt: &SetFontStmt) -> Self::Result;
+    fn visit_set_position(&mut self, stmt: &SetPositionStmt) -> Self::Result;
+    fn visit_set_xy(&mut self, stmt: &SetXYStmt) -> Self::Result;
+    fn visit_set_heading(&mut self, stmt: &SetHeadingStmt) -> Self::Result;
+    fn visit_home(&mut self) -> Self::Result;
+    fn visit_clean(&mut self) -> Self::Result;
+    fn visit_clear_screen(&mut self) -> Self::Result;
+    fn visit_pen_up(&mut self) -> Self::Result;
+    fn visit_pen_down(&mut self) -> Self::Result;
+    fn visit_set_pen_mode(&mut self, mode: &PenMode) -> Self::Result;
+    fn visit_show_turtle(&mut self) -> Self::Result;
+    fn visit_hide_turtle(&mut self) -> Self::Result;
+    fn visit_stamp(&mut self) -> Self::Result;
+    fn visit_push_turtle(&mut self) -> Self::Result;
+    fn visit_pop_turtle(&mut self) -> Self::Result;
+    fn visit_set_shape(&mut self, stmt: &SetShapeStmt) -> Self::Result;
+    fn visit_set_boundary_mode(&mut self, mode: &BoundaryMode) -> Self::Result;
+    fn visit_tell(&mut self, stmt: &TellStmt) -> Self::Result;
+    fn visit_ask(&mut self, stmt: &AskStmt) -> Self::Result;
+    fn visit_make(&mut self, stmt: &MakeStmt) -> Self::Result;
+    fn visit_rerandom(&mut self, stmt: &RerandomStmt) -> Self::Result;
+    fn visit_set_recursion_depth(&mut self, stmt: &SetRecursionDepthStmt) -> Self::Result;
+    fn visit_print(&mut self, stmt: &PrintStmt) -> Self::Result;
+    fn visit_procedure_declaration(&mut self, stmt: &ProcedureDeclarationStmt) -> Self::Result;
+    fn visit_procedure_call(&mut self, stmt: &ProcedureCallStmt) -> Self::Result;
+    fn visit_stop(&mut self) -> Self::Result;
+    fn visit_output(&mut self, stmt: &OutputStmt) -> Self::Result;
 }
 
 pub trait ExprVisitable {
-    fn accept<V: ExprVisitor>(&mut self, visitor: &mut V) -> V::Result;
+    fn accept<V: ExprVisitor>(&self, visitor: &mut V) -> V::Result;
 }
 
 pub trait StmtVisitable {
-    fn accept<V: StmtVisitor>(&mut self, visitor: &mut V) -> V::Result;
+    fn accept<V: StmtVisitor>(&self, visitor: &mut V) -> V::Result;
 }
 
 
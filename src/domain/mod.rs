@@ -1,6 +1,9 @@
+pub mod color;
+pub mod fill;
 pub mod geometry;
 pub mod interpreter;
 pub mod parser;
+pub mod random;
 pub mod turtle;
 pub mod tokenizer;
 pub mod errors;
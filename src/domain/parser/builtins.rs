@@ -0,0 +1,189 @@
+use phf::phf_map;
+
+/// Built-in operations that output a value and can be used inside expressions.
+/// Commands (e.g. `FD`) are keywords recognised by the tokenizer, whereas builtins are looked up by name
+/// so they share the namespace with user-defined procedures.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Builtin {
+    RepCount,
+    PenDownP,
+    XCor,
+    YCor,
+    Pos,
+    Heading,
+    Towards,
+    DistanceXY,
+    PenColor,
+    PenSize,
+    Hsv,
+    Who,
+
+    First,
+    ButFirst,
+    Last,
+    ButLast,
+    Item,
+    FPut,
+    LPut,
+    Count,
+    EmptyP,
+    Sentence,
+    Word,
+    List,
+
+    Sin,
+    Cos,
+    Tan,
+    ArcTan,
+    Sqrt,
+    Power,
+    Exp,
+    Ln,
+    Abs,
+    Int,
+    Round,
+    Remainder,
+    Modulo,
+    Random,
+    Pi,
+}
+
+pub static NAME_TO_BUILTIN: phf::Map<&'static str, Builtin> = phf_map! {
+    "repcount" => Builtin::RepCount,
+    "pendownp" => Builtin::PenDownP,
+    "xcor" => Builtin::XCor,
+    "ycor" => Builtin::YCor,
+    "pos" => Builtin::Pos,
+    "heading" => Builtin::Heading,
+    "towards" => Builtin::Towards,
+    "distancexy" => Builtin::DistanceXY,
+    "pencolor" => Builtin::PenColor,
+    "pensize" => Builtin::PenSize,
+    "hsv" => Builtin::Hsv,
+    "who" => Builtin::Who,
+
+    "first" => Builtin::First,
+    "butfirst" => Builtin::ButFirst,
+    "bf" => Builtin::ButFirst,
+    "last" => Builtin::Last,
+    "butlast" => Builtin::ButLast,
+    "bl" => Builtin::ButLast,
+    "item" => Builtin::Item,
+    "fput" => Builtin::FPut,
+    "lput" => Builtin::LPut,
+    "count" => Builtin::Count,
+    "emptyp" => Builtin::EmptyP,
+    "sentence" => Builtin::Sentence,
+    "se" => Builtin::Sentence,
+    "word" => Builtin::Word,
+    "list" => Builtin::List,
+
+    "sin" => Builtin::Sin,
+    "cos" => Builtin::Cos,
+    "tan" => Builtin::Tan,
+    "arctan" => Builtin::ArcTan,
+    "sqrt" => Builtin::Sqrt,
+    "power" => Builtin::Power,
+    "exp" => Builtin::Exp,
+    "ln" => Builtin::Ln,
+    "abs" => Builtin::Abs,
+    "int" => Builtin::Int,
+    "round" => Builtin::Round,
+    "remainder" => Builtin::Remainder,
+    "modulo" => Builtin::Modulo,
+    "random" => Builtin::Random,
+    "pi" => Builtin::Pi,
+};
+
+impl Builtin {
+    pub fn arity(&self) -> usize {
+        match self {
+            Builtin::RepCount
+            | Builtin::PenDownP
+            | Builtin::XCor
+            | Builtin::YCor
+            | Builtin::Pos
+            | Builtin::Heading
+            | Builtin::PenColor
+            | Builtin::PenSize
+            | Builtin::Who
+            | Builtin::Pi => 0,
+
+            Builtin::First
+            | Builtin::ButFirst
+            | Builtin::Last
+            | Builtin::ButLast
+            | Builtin::Count
+            | Builtin::EmptyP
+            | Builtin::Sin
+            | Builtin::Cos
+            | Builtin::Tan
+            | Builtin::ArcTan
+            | Builtin::Sqrt
+            | Builtin::Exp
+            | Builtin::Ln
+            | Builtin::Abs
+            | Builtin::Int
+            | Builtin::Round
+            | Builtin::Towards
+            | Builtin::Random => 1,
+            Builtin::Item
+            | Builtin::FPut
+            | Builtin::LPut
+            | Builtin::Sentence
+            | Builtin::Word
+            | Builtin::List
+            | Builtin::Power
+            | Builtin::Remainder
+            | Builtin::Modulo
+            | Builtin::DistanceXY => 2,
+            Builtin::Hsv => 3,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::RepCount => "REPCOUNT",
+            Builtin::PenDownP => "PENDOWNP",
+            Builtin::XCor => "XCOR",
+            Builtin::YCor => "YCOR",
+            Builtin::Pos => "POS",
+            Builtin::Heading => "HEADING",
+            Builtin::Towards => "TOWARDS",
+            Builtin::DistanceXY => "DISTANCEXY",
+            Builtin::PenColor => "PENCOLOR",
+            Builtin::PenSize => "PENSIZE",
+            Builtin::Hsv => "HSV",
+            Builtin::Who => "WHO",
+
+            Builtin::First => "FIRST",
+            Builtin::ButFirst => "BUTFIRST",
+            Builtin::Last => "LAST",
+            Builtin::ButLast => "BUTLAST",
+            Builtin::Item => "ITEM",
+            Builtin::FPut => "FPUT",
+            Builtin::LPut => "LPUT",
+            Builtin::Count => "COUNT",
+            Builtin::EmptyP => "EMPTYP",
+            Builtin::Sentence => "SENTENCE",
+            Builtin::Word => "WORD",
+            Builtin::List => "LIST",
+
+            Builtin::Sin => "SIN",
+            Builtin::Cos => "COS",
+            Builtin::Tan => "TAN",
+            Builtin::ArcTan => "ARCTAN",
+            Builtin::Sqrt => "SQRT",
+            Builtin::Power => "POWER",
+            Builtin::Exp => "EXP",
+            Builtin::Ln => "LN",
+            Builtin::Abs => "ABS",
+            Builtin::Int => "INT",
+            Builtin::Round => "ROUND",
+            Builtin::Remainder => "REMAINDER",
+            Builtin::Modulo => "MODULO",
+            Builtin::Random => "RANDOM",
+            Builtin::Pi => "PI",
+        }
+    }
+}
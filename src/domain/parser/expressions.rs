@@ -1,21 +1,102 @@
+use crate::domain::color::Color;
 use crate::domain::interpreter::visitor::{ExprVisitable, ExprVisitor};
+use crate::domain::parser::builtins::Builtin;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Literal {
     Number(f64),
-    Color(String),
+    Color(Color),
+    Word(String),
+    List(Vec<Literal>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct VariableExpr {
+    // Name is stored in lowercase and without the leading colon.
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum UnaryOperator {
+    Negate,
+    Not,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LogicalOperator {
+    And,
+    Or,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnaryExpr {
+    pub operator: UnaryOperator,
+    pub right: Box<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BinaryExpr {
+    pub left: Box<Expr>,
+    pub operator: BinaryOperator,
+    pub right: Box<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LogicalExpr {
+    pub left: Box<Expr>,
+    pub operator: LogicalOperator,
+    pub right: Box<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BuiltinCallExpr {
+    pub builtin: Builtin,
+    pub arguments: Vec<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProcedureCallExpr {
+    // Procedure has to OUTPUT a value when called inside an expression.
+    pub name: String,
+    pub arguments: Vec<Expr>,
 }
 
 // Expressions result in a value.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expr {
     Literal(Literal),
+    Variable(VariableExpr),
+    Unary(UnaryExpr),
+    Binary(BinaryExpr),
+    Logical(LogicalExpr),
+    BuiltinCall(BuiltinCallExpr),
+    ProcedureCall(ProcedureCallExpr),
 }
 
 impl ExprVisitable for Expr {
-    fn accept<V: ExprVisitor>(&mut self, visitor: &mut V) -> V::Result {
+    fn accept<V: ExprVisitor>(&self, visitor: &mut V) -> V::Result {
         match self {
             Expr::Literal(literal) => visitor.visit_literal(literal),
+            Expr::Variable(expr) => visitor.visit_variable(expr),
+            Expr::Unary(expr) => visitor.visit_unary(expr),
+            Expr::Binary(expr) => visitor.visit_binary(expr),
+            Expr::Logical(expr) => visitor.visit_logical(expr),
+            Expr::BuiltinCall(expr) => visitor.visit_builtin_call(expr),
+            Expr::ProcedureCall(expr) => visitor.visit_procedure_call(expr),
         }
     }
-}
\ No newline at end of file
+}
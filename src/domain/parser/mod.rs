@@ -1,3 +1,4 @@
+pub mod builtins;
 pub mod parser;
 pub mod expressions;
 pub mod statements;
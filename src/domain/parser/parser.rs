@@ -1,23 +1,39 @@
+use std::collections::HashMap;
 use std::iter::Peekable;
+use std::vec::IntoIter;
 
-use log::info;
-
+use crate::domain::color::Color;
 use crate::domain::errors::InterpreterError;
-use crate::domain::parser::expressions::{Expr, Literal};
-use crate::domain::parser::statements::{MoveBackwardStmt, MoveForwardStmt, RepeatStmt, RotateLeftStmt, RotateRightStmt, SetColorStmt, Stmt};
+use crate::domain::geometry::BoundaryMode;
+use crate::domain::parser::builtins::NAME_TO_BUILTIN;
+use crate::domain::parser::expressions::{BinaryExpr, BuiltinCallExpr, BinaryOperator, Expr, Literal, LogicalExpr, LogicalOperator, ProcedureCallExpr, UnaryExpr, UnaryOperator, VariableExpr};
+use crate::domain::parser::statements::{ArcStmt, AskStmt, FilledStmt, ForeverStmt, ForStmt, IfStmt, LabelStmt, MakeStmt, MoveBackwardStmt, MoveForwardStmt, OutputStmt, PrintStmt, PrintStyle, ProcedureCallStmt, ProcedureDeclarationStmt, RepeatStmt, RerandomStmt, RotateLeftStmt, RotateRightStmt, SetBackgroundStmt, SetColorStmt, SetDashStmt, SetPaletteStmt, SetFontStmt, SetHeadingStmt, SetLabelHeightStmt, SetPositionStmt, SetXYStmt, SetLineCapStmt, SetLineJoinStmt, SetPenSizeStmt, SetRecursionDepthStmt, SetShapeStmt, Stmt, TellStmt, WhileStmt};
 use crate::domain::parser::statements::Stmt::Block;
-use crate::domain::tokenizer::token::{Token, TokenType};
+use crate::domain::tokenizer::token::{Spelling, Token, TokenType};
+use crate::domain::turtle::PenMode;
 use crate::domain::tokenizer::token::TokenType::{LeftBracketToken, RightBracketToken};
 use crate::domain::tokenizer::tokenizer::Tokenizer;
 
-pub struct Parser<'a> {
-    tokens: Peekable<Tokenizer<'a>>,
+pub struct Parser {
+    tokens: Peekable<IntoIter<Token>>,
+    // Source spelling of the tokens, consumed together with them.
+    spellings: IntoIter<Spelling>,
+    // Number of parameters for each procedure declared in the source, indexed by procedure name.
+    // Logo calls do not delimit their arguments, so we need to know the arity upfront.
+    procedure_arities: HashMap<String, usize>,
+    // First error reported by the tokenizer. It is more precise than the parser error the illegal token would cause.
+    tokenizer_error: Option<InterpreterError>,
 }
 
-impl<'a> Parser<'a> {
-    pub fn new_from_str(source: &'a str) -> Self {
+impl Parser {
+    pub fn new_from_str(source: &str) -> Self {
+        let mut tokenizer = Tokenizer::new(source);
+        let tokens: Vec<Token> = tokenizer.by_ref().collect();
         Parser {
-            tokens: Tokenizer::new(source).peekable(),
+            procedure_arities: find_procedure_arities(&tokens),
+            tokens: tokens.into_iter().peekable(),
+            spellings: tokenizer.spellings().to_vec().into_iter(),
+            tokenizer_error: tokenizer.errors().first().cloned(),
         }
     }
 
@@ -38,7 +54,17 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn peek_is_builtin(&mut self) -> bool {
+        match self.tokens.peek() {
+            Some(Token::IdentifierToken(_, name)) => NAME_TO_BUILTIN.contains_key(name.to_ascii_lowercase().as_str()),
+            _ => false
+        }
+    }
+
     pub fn parse(&mut self) -> Result<Vec<Stmt>, InterpreterError> {
+        if let Some(error) = self.tokenizer_error.take() {
+            return Err(error);
+        }
         let mut statements = Vec::new();
         let mut errors = Vec::new();
         while !self.has_reached_end() {
@@ -61,7 +87,7 @@ impl<'a> Parser<'a> {
 
     fn consume_if(&mut self, token_type: TokenType) -> Option<Token> {
         if self.peek_is(token_type) {
-            return self.tokens.next();
+            return self.consume();
         }
         None
     }
@@ -85,46 +111,27 @@ impl<'a> Parser<'a> {
 
     fn consume(&mut self) -> Option<Token> {
         // Consumes next token regardless of its type.
+        self.spellings.next();
         self.tokens.next()
     }
 
-    pub fn consume_block(&mut self) -> Result<Vec<Token>, InterpreterError> {
+    fn consume_with_spelling(&mut self) -> Option<(Token, Spelling)> {
+        let spelling = self.spellings.next()?;
+        self.tokens.next().map(|token| (token, spelling))
+    }
+
+    fn parse_block(&mut self) -> Result<Vec<Stmt>, InterpreterError> {
         // Expects to start with [ and end with ]
-        let _ = self.consume_expecting(LeftBracketToken, "Expected block. Block has to start with opening bracket `[`. Opening bracket is missing.");
-        let mut open_brackets_count = 1;
-        let mut all_block_tokens: Vec<Token> = Vec::new();
-        while !self.has_reached_end() {
-            let peek =
-                self.tokens.peek()
-                .expect("Must exist because tokenizer did not reach the end yet.");
-            match peek {
-                x if x.is_type(LeftBracketToken) => {
-                    open_brackets_count += 1;
-                    all_block_tokens.push(self.consume().unwrap());
-                },
-                x if x.is_type(RightBracketToken) => {
-                    if open_brackets_count < 1 {
-                        return Err(InterpreterError::Parser {
-                            // FIXME: The line number has to come from the token.
-                            line: 0,
-                            message: "Invalid number of closing brackets inside a block. There are more closing brackets than expected.".into()
-                        })
-                    }
-                    if open_brackets_count == 1 {
-                        let _ = self.consume_expecting(RightBracketToken, "Expected block. Block has to end with closing bracket `]`. Closing bracket is missing.");
-                        return Ok(all_block_tokens);
-                    }
-                    open_brackets_count -= 1;
-                    all_block_tokens.push(self.consume().unwrap());
-                },
-                _ => all_block_tokens.push(self.consume().unwrap())
+        self.consume_expecting(LeftBracketToken, "Expected block. Block has to start with opening bracket `[`. Opening bracket is missing.")?;
+        let mut statements = Vec::new();
+        while !self.peek_is(RightBracketToken) {
+            if self.has_reached_end() {
+                return Err(self.make_error("Expected block. Block has to end with closing bracket `]`. Closing bracket is missing."));
             }
+            statements.push(self.parse_statement()?);
         }
-        Err(InterpreterError::Parser {
-            // FIXME: The line number has to come from the last token.
-            line: 0,
-            message: "Invalid number of closing brackets inside a block. There are more closing brackets than expected.".into()
-        })
+        self.consume_expecting(RightBracketToken, "Expected block. Block has to end with closing bracket `]`. Closing bracket is missing.")?;
+        Ok(statements)
     }
 
     fn has_reached_end(&mut self) -> bool {
@@ -132,16 +139,87 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_declaration(&mut self) -> Result<Stmt, InterpreterError> {
-        // Branching would occur here if language had support for functions, classes and variables.
-        // For now, Rusty Turtle only supports statements.
-        self.parse_statement()
+        // Procedures can only be declared at the top level, hence they are not parsed as statements.
+        if let Some(to_token) = self.consume_if(TokenType::ToToken) {
+            self.parse_procedure_declaration(to_token.line())
+        } else {
+            self.parse_statement()
+        }
+    }
+
+    fn parse_procedure_declaration(&mut self, line: usize) -> Result<Stmt, InterpreterError> {
+        let name = match self.consume_expecting(TokenType::IdentifierToken, "Expecting procedure name after TO.")? {
+            Token::IdentifierToken(_, name) => name.to_ascii_lowercase(),
+            _ => panic!("Expected Identifier"),
+        };
+        if NAME_TO_BUILTIN.contains_key(name.as_str()) {
+            return Err(InterpreterError::Parser {
+                line,
+                message: format!("`{}` is a primitive and can't be redefined.", name),
+            });
+        }
+        let mut parameters = Vec::new();
+        while let Some(token) = self.consume_if(TokenType::VariableToken) {
+            match token {
+                Token::VariableToken(_, parameter) => parameters.push(parameter),
+                _ => panic!("Expected Variable"),
+            }
+        }
+        let mut body = Vec::new();
+        while self.consume_if(TokenType::EndToken).is_none() {
+            if self.has_reached_end() {
+                return Err(InterpreterError::Parser {
+                    line,
+                    message: format!("Procedure `{}` is missing END.", name),
+                });
+            }
+            body.push(self.parse_statement()?);
+        }
+        Ok(Stmt::ProcedureDeclaration(
+            ProcedureDeclarationStmt {
+                name,
+                parameters,
+                body: Box::new(Block(body)),
+            }
+        ))
     }
 
     fn parse_statement(&mut self) -> Result<Stmt, InterpreterError> {
         if self.consume_if(TokenType::RepeatLoopToken).is_some() {
             self.parse_repeat_loop()
+        } else if self.consume_if(TokenType::WhileLoopToken).is_some() {
+            self.parse_while_loop(false)
+        } else if self.consume_if(TokenType::UntilLoopToken).is_some() {
+            self.parse_while_loop(true)
+        } else if self.consume_if(TokenType::ForeverLoopToken).is_some() {
+            self.parse_forever_loop()
+        } else if self.consume_if(TokenType::ForLoopToken).is_some() {
+            self.parse_for_loop()
+        } else if self.consume_if(TokenType::IfToken).is_some() {
+            self.parse_if_stmt()
+        } else if self.consume_if(TokenType::IfElseToken).is_some() {
+            self.parse_if_else_stmt()
         } else if self.consume_if(TokenType::SetColorCommandToken).is_some() {
             self.parse_set_color_command_stmt()
+        } else if self.consume_if(TokenType::SetBackgroundToken).is_some() {
+            let color = self.parse_argument("Expecting color after SETBACKGROUND.")?;
+            Ok(Stmt::SetBackground(SetBackgroundStmt { color }))
+        } else if self.consume_if(TokenType::SetPaletteToken).is_some() {
+            let index = self.parse_argument("Expecting palette index after SETPALETTE.")?;
+            let color = self.parse_argument("Expecting color after SETPALETTE index.")?;
+            Ok(Stmt::SetPalette(SetPaletteStmt { index, color }))
+        } else if self.consume_if(TokenType::SetPenSizeToken).is_some() {
+            let size = self.parse_argument("Expecting number after SETPENSIZE.")?;
+            Ok(Stmt::SetPenSize(SetPenSizeStmt { size }))
+        } else if self.consume_if(TokenType::SetLineCapToken).is_some() {
+            let cap = self.parse_argument("Expecting \"butt, \"round or \"square after SETLINECAP.")?;
+            Ok(Stmt::SetLineCap(SetLineCapStmt { cap }))
+        } else if self.consume_if(TokenType::SetLineJoinToken).is_some() {
+            let join = self.parse_argument("Expecting \"miter, \"round or \"bevel after SETLINEJOIN.")?;
+            Ok(Stmt::SetLineJoin(SetLineJoinStmt { join }))
+        } else if self.consume_if(TokenType::SetDashToken).is_some() {
+            let pattern = self.parse_argument("Expecting list of lengths after SETDASH, e.g. SETDASH [5 3].")?;
+            Ok(Stmt::SetDash(SetDashStmt { pattern }))
         } else if self.consume_if(TokenType::ForwardCommandToken).is_some() {
             self.parse_forward_command_stmt()
         } else if self.consume_if(TokenType::BackwardCommandToken).is_some() {
@@ -150,55 +228,314 @@ impl<'a> Parser<'a> {
             self.parse_right_command_stmt()
         } else if self.consume_if(TokenType::TurnLeftCommandToken).is_some() {
             self.parse_left_command_stmt()
-        }else {
+        } else if self.consume_if(TokenType::ArcToken).is_some() {
+            let angle = self.parse_argument("Expecting angle after ARC.")?;
+            let radius = self.parse_argument("Expecting radius after ARC angle.")?;
+            Ok(Stmt::Arc(ArcStmt { angle, radius }))
+        } else if self.consume_if(TokenType::CircleToken).is_some() {
+            let radius = self.parse_argument("Expecting radius after CIRCLE.")?;
+            Ok(Stmt::Arc(ArcStmt { angle: Expr::Literal(Literal::Number(360.0)), radius }))
+        } else if self.consume_if(TokenType::FilledToken).is_some() {
+            let color = self.parse_argument("Expecting color after FILLED.")?;
+            let body = self.parse_block()?;
+            Ok(Stmt::Filled(FilledStmt { color, body: Box::new(Block(body)) }))
+        } else if self.consume_if(TokenType::TellToken).is_some() {
+            let turtles = self.parse_argument("Expecting turtle number or list after TELL, e.g. TELL [1 2 3].")?;
+            Ok(Stmt::Tell(TellStmt { turtles }))
+        } else if self.consume_if(TokenType::AskToken).is_some() {
+            let turtles = self.parse_argument("Expecting turtle number or list after ASK.")?;
+            let body = self.parse_block()?;
+            Ok(Stmt::Ask(AskStmt { turtles, body: Box::new(Block(body)) }))
+        } else if self.consume_if(TokenType::FillToken).is_some() {
+            Ok(Stmt::Fill)
+        } else if self.consume_if(TokenType::LabelToken).is_some() {
+            let text = self.parse_argument("Expecting word or list after LABEL, e.g. LABEL \"hello.")?;
+            Ok(Stmt::Label(LabelStmt { text }))
+        } else if self.consume_if(TokenType::SetLabelHeightToken).is_some() {
+            let height = self.parse_argument("Expecting number after SETLABELHEIGHT.")?;
+            Ok(Stmt::SetLabelHeight(SetLabelHeightStmt { height }))
+        } else if self.consume_if(TokenType::SetFontToken).is_some() {
+            let font = self.parse_argument("Expecting font name after SETFONT, e.g. SETFONT \"serif.")?;
+            Ok(Stmt::SetFont(SetFontStmt { font }))
+        } else if self.consume_if(TokenType::SetPosToken).is_some() {
+            let position = self.parse_argument("Expecting list of two numbers after SETPOS, e.g. SETPOS [10 20].")?;
+            Ok(Stmt::SetPosition(SetPositionStmt { position }))
+        } else if self.consume_if(TokenType::SetXYToken).is_some() {
+            let x = self.parse_argument("Expecting x coordinate after SETXY.")?;
+            let y = self.parse_argument("Expecting y coordinate after SETXY.")?;
+            Ok(Stmt::SetXY(SetXYStmt { x: Some(x), y: Some(y) }))
+        } else if self.consume_if(TokenType::SetXToken).is_some() {
+            let x = self.parse_argument("Expecting x coordinate after SETX.")?;
+            Ok(Stmt::SetXY(SetXYStmt { x: Some(x), y: None }))
+        } else if self.consume_if(TokenType::SetYToken).is_some() {
+            let y = self.parse_argument("Expecting y coordinate after SETY.")?;
+            Ok(Stmt::SetXY(SetXYStmt { x: None, y: Some(y) }))
+        } else if self.consume_if(TokenType::SetHeadingToken).is_some() {
+            let heading = self.parse_argument("Expecting heading in degrees after SETHEADING.")?;
+            Ok(Stmt::SetHeading(SetHeadingStmt { heading }))
+        } else if self.consume_if(TokenType::HomeToken).is_some() {
+            Ok(Stmt::Home)
+        } else if self.consume_if(TokenType::CleanToken).is_some() {
+            Ok(Stmt::Clean)
+        } else if self.consume_if(TokenType::ClearScreenToken).is_some() {
+            Ok(Stmt::ClearScreen)
+        } else if self.consume_if(TokenType::PenUpToken).is_some() {
+            Ok(Stmt::PenUp)
+        } else if self.consume_if(TokenType::PenDownToken).is_some() {
+            Ok(Stmt::PenDown)
+        } else if self.consume_if(TokenType::PenPaintToken).is_some() {
+            Ok(Stmt::SetPenMode(PenMode::Paint))
+        } else if self.consume_if(TokenType::PenEraseToken).is_some() {
+            Ok(Stmt::SetPenMode(PenMode::Erase))
+        } else if self.consume_if(TokenType::PenReverseToken).is_some() {
+            Ok(Stmt::SetPenMode(PenMode::Reverse))
+        } else if self.consume_if(TokenType::ShowTurtleToken).is_some() {
+            Ok(Stmt::ShowTurtle)
+        } else if self.consume_if(TokenType::HideTurtleToken).is_some() {
+            Ok(Stmt::HideTurtle)
+        } else if self.consume_if(TokenType::StampToken).is_some() {
+            Ok(Stmt::Stamp)
+        } else if self.consume_if(TokenType::PushTurtleToken).is_some() {
+            Ok(Stmt::PushTurtle)
+        } else if self.consume_if(TokenType::PopTurtleToken).is_some() {
+            Ok(Stmt::PopTurtle)
+        } else if self.consume_if(TokenType::SetShapeToken).is_some() {
+            let shape = self.parse_argument("Expecting shape name or SVG path data after SETSHAPE.")?;
+            Ok(Stmt::SetShape(SetShapeStmt { shape }))
+        } else if self.consume_if(TokenType::WrapToken).is_some() {
+            Ok(Stmt::SetBoundaryMode(BoundaryMode::Wrap))
+        } else if self.consume_if(TokenType::FenceToken).is_some() {
+            Ok(Stmt::SetBoundaryMode(BoundaryMode::Fence))
+        } else if self.consume_if(TokenType::WindowToken).is_some() {
+            Ok(Stmt::SetBoundaryMode(BoundaryMode::Window))
+        } else if self.consume_if(TokenType::StopToken).is_some() {
+            Ok(Stmt::Stop)
+        } else if self.consume_if(TokenType::OutputToken).is_some() {
+            let value = self.parse_argument("Expecting value after OUTPUT.")?;
+            Ok(Stmt::Output(OutputStmt { value }))
+        } else if self.consume_if(TokenType::MakeToken).is_some() {
+            self.parse_make_stmt()
+        } else if self.consume_if(TokenType::PrintToken).is_some() {
+            self.parse_print_stmt(PrintStyle::Print)
+        } else if self.consume_if(TokenType::ShowToken).is_some() {
+            self.parse_print_stmt(PrintStyle::Show)
+        } else if self.consume_if(TokenType::TypeToken).is_some() {
+            self.parse_print_stmt(PrintStyle::Type)
+        } else if self.consume_if(TokenType::RerandomToken).is_some() {
+            let seed = self.parse_argument("Expecting seed number after RERANDOM.")?;
+            Ok(Stmt::Rerandom(RerandomStmt { seed }))
+        } else if self.consume_if(TokenType::SetRecursionDepthToken).is_some() {
+            let depth = self.parse_argument("Expecting number after SETRECURSIONDEPTH.")?;
+            Ok(Stmt::SetRecursionDepth(SetRecursionDepthStmt { depth }))
+        } else if self.peek_is(TokenType::IdentifierToken) && !self.peek_is_builtin() {
+            self.parse_procedure_call_stmt()
+        } else {
             self.parse_expression_stmt()
         }
     }
 
     fn parse_set_color_command_stmt(&mut self)-> Result<Stmt, InterpreterError> {
-        let color = self.consume_expecting(TokenType::ColorToken, "Expecting HEX color after COLOR command.")?;
-        match color {
-            Token::ColorToken(_, _) => Ok(Stmt::SetColor(SetColorStmt {color: color.lexeme()})),
-            _ => Err(self.make_error("Expected number."))
-        }
+        let color = self.parse_argument("Expecting color after COLOR command, e.g. COLOR #ff0000, COLOR \"red or COLOR [255 0 0].")?;
+        Ok(Stmt::SetColor(SetColorStmt { color }))
     }
 
     fn parse_forward_command_stmt(&mut self)-> Result<Stmt, InterpreterError> {
-        let number = self.consume_expecting(TokenType::NumberToken, "Expecting number after forward command.")?;
-        match number {
-            Token::NumberToken(_, value) => Ok(Stmt::MoveForward(MoveForwardStmt {distance: value})),
-            _ => Err(self.make_error("Expected number."))
-        }
+        let distance = self.parse_argument("Expecting number after forward command.")?;
+        Ok(Stmt::MoveForward(MoveForwardStmt { distance }))
     }
 
     fn parse_back_command_stmt(&mut self)-> Result<Stmt, InterpreterError> {
-        let number = self.consume_expecting(TokenType::NumberToken, "Expecting number after back command.")?;
-        match number {
-            Token::NumberToken(_, value) => Ok(Stmt::MoveBack(MoveBackwardStmt {distance: value})),
-            _ => Err(self.make_error("Expected number."))
-        }
+        let distance = self.parse_argument("Expecting number after back command.")?;
+        Ok(Stmt::MoveBack(MoveBackwardStmt { distance }))
     }
 
     fn parse_right_command_stmt(&mut self)-> Result<Stmt, InterpreterError> {
-        let number = self.consume_expecting(TokenType::NumberToken, "Expecting number after turn right command.")?;
-        match number {
-            Token::NumberToken(_, value) => Ok(Stmt::RotateRight(RotateRightStmt {angular_distance: value})),
-            _ => Err(self.make_error("Expected number."))
-        }
+        let angular_distance = self.parse_argument("Expecting number after turn right command.")?;
+        Ok(Stmt::RotateRight(RotateRightStmt { angular_distance }))
     }
 
     fn parse_left_command_stmt(&mut self)-> Result<Stmt, InterpreterError> {
-        let number = self.consume_expecting(TokenType::NumberToken, "Expecting number after turn left command.")?;
-        match number {
-            Token::NumberToken(_, value) => Ok(Stmt::RotateLeft(RotateLeftStmt {angular_distance: value})),
-            _ => Err(self.make_error("Expected number."))
+        let angular_distance = self.parse_argument("Expecting number after turn left command.")?;
+        Ok(Stmt::RotateLeft(RotateLeftStmt { angular_distance }))
+    }
+
+    fn parse_make_stmt(&mut self) -> Result<Stmt, InterpreterError> {
+        let name = match self.consume_expecting(TokenType::WordToken, "Expecting quoted variable name after MAKE, e.g. MAKE \"size 50.")? {
+            Token::WordToken(_, name) => name.to_ascii_lowercase(),
+            _ => panic!("Expected Word"),
+        };
+        let value = self.parse_argument("Expecting value after MAKE variable name.")?;
+        Ok(Stmt::Make(MakeStmt { name, value }))
+    }
+
+    fn parse_print_stmt(&mut self, style: PrintStyle) -> Result<Stmt, InterpreterError> {
+        let value = self.parse_argument("Expecting value to print.")?;
+        Ok(Stmt::Print(PrintStmt { value, style }))
+    }
+
+    fn parse_procedure_call_stmt(&mut self) -> Result<Stmt, InterpreterError> {
+        let (name, arguments) = self.parse_procedure_call()?;
+        Ok(Stmt::ProcedureCall(ProcedureCallStmt { name, arguments }))
+    }
+
+    fn parse_procedure_call(&mut self) -> Result<(String, Vec<Expr>), InterpreterError> {
+        let name = match self.consume() {
+            Some(Token::IdentifierToken(_, name)) => name.to_ascii_lowercase(),
+            _ => panic!("Expected Identifier"),
+        };
+        let arity = match self.procedure_arities.get(&name) {
+            Some(arity) => *arity,
+            None => return Err(self.make_error(format!("I don't know how to `{}`. There is no such command or procedure.", name))),
+        };
+        let mut arguments = Vec::with_capacity(arity);
+        for _ in 0..arity {
+            arguments.push(self.parse_argument("Procedure call is missing an argument.")?);
         }
+        Ok((name, arguments))
     }
 
     fn parse_expression_stmt(&mut self) -> Result<Stmt, InterpreterError> {
-        // Currently Rust Turtle only supports Number literals.
-        Ok(Stmt::Expression(self.parse_primary()?))
+        Ok(Stmt::Expression(self.parse_expression()?))
+    }
+
+    fn peek_starts_expression(&mut self) -> bool {
+        self.peek_is(TokenType::NumberToken)
+            || self.peek_is(TokenType::ColorToken)
+            || self.peek_is(TokenType::VariableToken)
+            || self.peek_is(TokenType::WordToken)
+            || self.peek_is(LeftBracketToken)
+            || self.peek_is(TokenType::LeftParenthesisToken)
+            || self.peek_is(TokenType::MinusToken)
+            || self.peek_is(TokenType::UnaryMinusToken)
+            || self.peek_is(TokenType::NotToken)
+            || self.peek_is(TokenType::AndToken)
+            || self.peek_is(TokenType::OrToken)
+            || self.peek_is(TokenType::IdentifierToken)
     }
+
+    fn parse_argument(&mut self, error_message: &'static str) -> Result<Expr, InterpreterError> {
+        // Used by commands so the error points out which command is missing its input.
+        if !self.peek_starts_expression() {
+            return Err(self.make_error(error_message));
+        }
+        self.parse_expression()
+    }
+
+    fn parse_expression(&mut self) -> Result<Expr, InterpreterError> {
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, InterpreterError> {
+        let mut expr = self.parse_term()?;
+        loop {
+            let operator = if self.consume_if(TokenType::EqualToken).is_some() {
+                BinaryOperator::Equal
+            } else if self.consume_if(TokenType::NotEqualToken).is_some() {
+                BinaryOperator::NotEqual
+            } else if self.consume_if(TokenType::LessToken).is_some() {
+                BinaryOperator::Less
+            } else if self.consume_if(TokenType::LessEqualToken).is_some() {
+                BinaryOperator::LessEqual
+            } else if self.consume_if(TokenType::GreaterToken).is_some() {
+                BinaryOperator::Greater
+            } else if self.consume_if(TokenType::GreaterEqualToken).is_some() {
+                BinaryOperator::GreaterEqual
+            } else {
+                return Ok(expr);
+            };
+            let right = self.parse_term()?;
+            expr = Expr::Binary(BinaryExpr { left: Box::new(expr), operator, right: Box::new(right) });
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, InterpreterError> {
+        let mut expr = self.parse_factor()?;
+        loop {
+            let operator = if self.consume_if(TokenType::PlusToken).is_some() {
+                BinaryOperator::Add
+            } else if self.consume_if(TokenType::MinusToken).is_some() {
+                BinaryOperator::Subtract
+            } else {
+                return Ok(expr);
+            };
+            let right = self.parse_factor()?;
+            expr = Expr::Binary(BinaryExpr { left: Box::new(expr), operator, right: Box::new(right) });
+        }
+    }
+
+    fn parse_factor(&mut self) -> Result<Expr, InterpreterError> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            let operator = if self.consume_if(TokenType::StarToken).is_some() {
+                BinaryOperator::Multiply
+            } else if self.consume_if(TokenType::SlashToken).is_some() {
+                BinaryOperator::Divide
+            } else {
+                return Ok(expr);
+            };
+            let right = self.parse_unary()?;
+            expr = Expr::Binary(BinaryExpr { left: Box::new(expr), operator, right: Box::new(right) });
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, InterpreterError> {
+        if self.consume_if(TokenType::UnaryMinusToken).is_some() || self.consume_if(TokenType::MinusToken).is_some() {
+            let right = self.parse_unary()?;
+            return Ok(Expr::Unary(UnaryExpr { operator: UnaryOperator::Negate, right: Box::new(right) }));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_list_literal(&mut self) -> Result<Literal, InterpreterError> {
+        // Content of a list is data, not code. Nothing inside the list is evaluated,
+        // e.g. `[FD :size]` is a list of two words.
+        // Words keep the spelling they were typed with, e.g. `[fd 10]` is not `[FD 10]`.
+        self.consume_expecting(LeftBracketToken, "Expected list. List has to start with opening bracket `[`.")?;
+        let mut items = Vec::new();
+        // Tokens typed without a space between them form one word, e.g. `-b` or `a+b`.
+        let mut word: Option<String> = None;
+        loop {
+            if self.peek_is(LeftBracketToken) {
+                items.extend(word.take().map(list_item));
+                items.push(self.parse_list_literal()?);
+                continue;
+            }
+            match self.consume_with_spelling() {
+                None => return Err(self.make_error("Expected list. List has to end with closing bracket `]`. Closing bracket is missing.")),
+                Some((Token::RightBracketToken(_), _)) => {
+                    items.extend(word.take().map(list_item));
+                    return Ok(Literal::List(items));
+                },
+                Some((_, spelling)) => match &mut word {
+                    Some(word) if !spelling.separated => word.push_str(&spelling.text),
+                    _ => items.extend(word.replace(spelling.text).map(list_item)),
+                },
+            }
+        }
+    }
+
+    fn parse_builtin_call(&mut self) -> Result<Expr, InterpreterError> {
+        let builtin = match self.consume() {
+            Some(Token::IdentifierToken(_, name)) => *NAME_TO_BUILTIN.get(name.to_ascii_lowercase().as_str()).expect("Builtin must exist."),
+            _ => panic!("Expected Identifier"),
+        };
+        let mut arguments = Vec::with_capacity(builtin.arity());
+        for _ in 0..builtin.arity() {
+            if !self.peek_starts_expression() {
+                return Err(self.make_error(format!("{} expects {} input(s).", builtin.name(), builtin.arity())));
+            }
+            arguments.push(self.parse_expression()?);
+        }
+        Ok(Expr::BuiltinCall(BuiltinCallExpr { builtin, arguments }))
+    }
+
+    fn parse_logical(&mut self, operator: LogicalOperator) -> Result<Expr, InterpreterError> {
+        // Like in UCBLogo, AND and OR are prefix operations, e.g. `AND :x > 0 :x < 10`.
+        let left = self.parse_argument("AND and OR expect two inputs. First input is missing.")?;
+        let right = self.parse_argument("AND and OR expect two inputs. Second input is missing.")?;
+        Ok(Expr::Logical(LogicalExpr { left: Box::new(left), operator, right: Box::new(right) }))
+    }
+
     fn parse_primary(&mut self) -> Result<Expr, InterpreterError> {
         if let Some(token) = self.consume_if(TokenType::NumberToken) {
             return match token {
@@ -206,49 +543,183 @@ impl<'a> Parser<'a> {
                 _ => panic!("Expected Number"),
             }
         }
-        info!("token {}", self.tokens.peek().unwrap().lexeme());
         if let Some(token) = self.consume_if(TokenType::ColorToken){
             return match token {
-                Token::ColorToken(_, value) => Ok(Expr::Literal(Literal::Color(value))),
+                Token::ColorToken(_, value) => Ok(Expr::Literal(Literal::Color(parse_color(&value)))),
                 _ => panic!("Expected Color"),
             }
         }
-        Err(self.make_error("Expected an expression while parsing primary."))
+        if let Some(token) = self.consume_if(TokenType::WordToken) {
+            return match token {
+                Token::WordToken(_, word) => Ok(Expr::Literal(Literal::Word(word))),
+                _ => panic!("Expected Word"),
+            }
+        }
+        if self.peek_is(LeftBracketToken) {
+            return Ok(Expr::Literal(self.parse_list_literal()?));
+        }
+        if let Some(token) = self.consume_if(TokenType::VariableToken) {
+            return match token {
+                Token::VariableToken(_, name) => Ok(Expr::Variable(VariableExpr { name })),
+                _ => panic!("Expected Variable"),
+            }
+        }
+        if self.peek_is_builtin() {
+            return self.parse_builtin_call();
+        }
+        if self.peek_is(TokenType::IdentifierToken) {
+            let (name, arguments) = self.parse_procedure_call()?;
+            return Ok(Expr::ProcedureCall(ProcedureCallExpr { name, arguments }));
+        }
+        if self.consume_if(TokenType::NotToken).is_some() {
+            let right = self.parse_argument("Expecting input after NOT.")?;
+            return Ok(Expr::Unary(UnaryExpr { operator: UnaryOperator::Not, right: Box::new(right) }));
+        }
+        if self.consume_if(TokenType::AndToken).is_some() {
+            return self.parse_logical(LogicalOperator::And);
+        }
+        if self.consume_if(TokenType::OrToken).is_some() {
+            return self.parse_logical(LogicalOperator::Or);
+        }
+        if self.consume_if(TokenType::LeftParenthesisToken).is_some() {
+            let expr = self.parse_expression()?;
+            self.consume_expecting(TokenType::RightParenthesisToken, "Expected closing parenthesis `)` after expression.")?;
+            return Ok(expr);
+        }
+        match self.tokens.peek().map(Token::lexeme) {
+            Some(lexeme) => Err(self.make_error(format!("Expected an expression but got `{}`.", lexeme))),
+            None => Err(self.make_error("Expected an expression while parsing primary.")),
+        }
     }
-    fn parse_repeat_loop(&mut self) -> Result<Stmt, InterpreterError> {
-        let number_of_repeats_token =
-            self.consume_expecting(
-                TokenType::NumberToken,
-                "Repeat statement must define a number of repeats. Parser didn't find number."
-            )?;
-        let number_of_repeats = match number_of_repeats_token {
-            Token::NumberToken(_, value) => value.round() as usize,
-            _ => panic!("TODO")
+    fn parse_bracketed_condition(&mut self, error_message: &'static str) -> Result<Expr, InterpreterError> {
+        // Like in UCBLogo, loop condition is written inside brackets, e.g. `WHILE [:i < 10] [...]`.
+        if self.consume_if(LeftBracketToken).is_some() {
+            let condition = self.parse_argument(error_message)?;
+            self.consume_expecting(RightBracketToken, "Expected closing bracket `]` after loop condition.")?;
+            Ok(condition)
+        } else {
+            self.parse_argument(error_message)
+        }
+    }
+
+    fn parse_while_loop(&mut self, is_until: bool) -> Result<Stmt, InterpreterError> {
+        let condition = self.parse_bracketed_condition("WHILE and UNTIL loops must define a condition.")?;
+        let condition = if is_until {
+            Expr::Unary(UnaryExpr { operator: UnaryOperator::Not, right: Box::new(condition) })
+        } else {
+            condition
+        };
+        let body = self.parse_block()?;
+        Ok(Stmt::While(WhileStmt { condition, body: Box::new(Block(body)) }))
+    }
+
+    fn parse_forever_loop(&mut self) -> Result<Stmt, InterpreterError> {
+        let body = self.parse_block()?;
+        Ok(Stmt::Forever(ForeverStmt { body: Box::new(Block(body)) }))
+    }
+
+    fn parse_for_loop(&mut self) -> Result<Stmt, InterpreterError> {
+        // Control list has a form of `[variable start end step]` where step is optional.
+        self.consume_expecting(LeftBracketToken, "FOR loop must start with a control list, e.g. FOR [i 1 10] [...].")?;
+        let variable = match self.consume_expecting(TokenType::IdentifierToken, "FOR control list must start with a variable name.")? {
+            Token::IdentifierToken(_, name) => name.to_ascii_lowercase(),
+            _ => panic!("Expected Identifier"),
+        };
+        let start = self.parse_argument("FOR control list is missing a start value.")?;
+        let end = self.parse_argument("FOR control list is missing an end value.")?;
+        let step = if self.peek_is(RightBracketToken) {
+            None
+        } else {
+            Some(self.parse_argument("FOR control list must end with closing bracket `]`.")?)
         };
-        let block_tokens = self.consume_block()?;
-        // This is not an ideal approach, but it should work fine for Rusty Turtle purpose.
-        // We turn tokens back into a string so we can feed a string to a new parsers that will parse only that block of code.
-        // Some inaccuracies are possible, like with turning NumberToken back to its string representation.
-        let block_code: String =
-            block_tokens
-                .iter()
-                .map(|x| x.lexeme().to_string())
-                .collect::<Vec<String>>()
-                .join(" ");
-        let mut block_parser = Parser::new_from_str(block_code.as_ref());
-        let block_statements = block_parser.parse()?;
+        self.consume_expecting(RightBracketToken, "FOR control list must end with closing bracket `]`.")?;
+        let body = self.parse_block()?;
+        Ok(Stmt::For(ForStmt { variable, start, end, step, body: Box::new(Block(body)) }))
+    }
+
+    fn parse_if_stmt(&mut self) -> Result<Stmt, InterpreterError> {
+        let condition = self.parse_argument("IF statement must define a condition.")?;
+        let then_branch = self.parse_block()?;
+        Ok(Stmt::If(
+            IfStmt {
+                condition,
+                then_branch: Box::new(Block(then_branch)),
+                else_branch: None,
+        }))
+    }
+
+    fn parse_if_else_stmt(&mut self) -> Result<Stmt, InterpreterError> {
+        let condition = self.parse_argument("IFELSE statement must define a condition.")?;
+        let then_branch = self.parse_block()?;
+        let else_branch = self.parse_block()?;
+        Ok(Stmt::If(
+            IfStmt {
+                condition,
+                then_branch: Box::new(Block(then_branch)),
+                else_branch: Some(Box::new(Block(else_branch))),
+        }))
+    }
+
+    fn parse_repeat_loop(&mut self) -> Result<Stmt, InterpreterError> {
+        let count = self.parse_argument("Repeat statement must define a number of repeats. Parser didn't find number.")?;
+        let block_statements = self.parse_block()?;
         Ok(Stmt::Repeat(
             RepeatStmt {
-                count: number_of_repeats,
+                count,
                 body: Box::new(Block(block_statements))
         }))
     }
 }
 
+fn parse_color(hex: &str) -> Color {
+    // Tokenizer only emits colour tokens for valid hex colours.
+    Color::from_hex(hex).expect("Color token must be a valid hex color.")
+}
+
+fn list_item(word: String) -> Literal {
+    // Numbers and colours inside lists stay numbers and colours, e.g. `SETPOS [10 -5]`.
+    let digits = word.strip_prefix('-').unwrap_or(&word);
+    if !digits.is_empty() && digits.chars().all(|x| x.is_ascii_digit() || x == '.') {
+        if let Ok(number) = word.parse::<f64>() {
+            return Literal::Number(number);
+        }
+    }
+    if word.starts_with('#') {
+        if let Some(color) = Color::from_hex(&word) {
+            return Literal::Color(color);
+        }
+    }
+    Literal::Word(word)
+}
+
+fn find_procedure_arities(tokens: &[Token]) -> HashMap<String, usize> {
+    // Procedures may be called before they are declared (e.g. recursion), so we scan all declarations first.
+    let mut arities = HashMap::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        if let (Token::ToToken(_), Some(Token::IdentifierToken(_, name))) = (&tokens[index], tokens.get(index + 1)) {
+            let arity =
+                tokens[index + 2..]
+                    .iter()
+                    .take_while(|x| x.is_type(TokenType::VariableToken))
+                    .count();
+            arities.insert(name.to_ascii_lowercase(), arity);
+            index += 2 + arity;
+        } else {
+            index += 1;
+        }
+    }
+    arities
+}
+
 
 #[cfg(test)]
 mod tests {
-    use crate::domain::parser::parser::Parser;
+    use crate::domain::color::Color;
+    use crate::domain::errors::InterpreterError;
+    use crate::domain::parser::expressions::{BinaryExpr, BinaryOperator, Expr, Literal, UnaryExpr, UnaryOperator, VariableExpr};
+    use crate::domain::parser::parser::{list_item, Parser};
+    use crate::domain::parser::statements::{ArcStmt, FilledStmt, MoveForwardStmt, ProcedureCallStmt, ProcedureDeclarationStmt, Stmt};
 
     #[test]
     fn use_tokenizers_next() {
@@ -256,4 +727,153 @@ mod tests {
         let result = Parser::new_from_str("REPEAT 30 [ FD 40 FD 50 ] BK 40").parse();
         println!("done")
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parsing_procedure_declaration_and_call() {
+        let result = Parser::new_from_str("SQUARE 10\nTO square :size\nFD :size\nEND").parse().unwrap();
+        assert_eq!(result[0], Stmt::ProcedureCall(ProcedureCallStmt {
+            name: "square".into(),
+            arguments: vec![Expr::Literal(Literal::Number(10.0))],
+        }));
+        assert_eq!(result[1], Stmt::ProcedureDeclaration(ProcedureDeclarationStmt {
+            name: "square".into(),
+            parameters: vec!["size".into()],
+            body: Box::new(Stmt::Block(vec![
+                Stmt::MoveForward(MoveForwardStmt { distance: Expr::Variable(VariableExpr { name: "size".into() }) }),
+            ])),
+        }));
+    }
+
+    #[test]
+    fn parsing_arithmetic_with_precedence() {
+        let result = Parser::new_from_str("FD 10 * 3 + -5").parse().unwrap();
+        let expected = Expr::Binary(BinaryExpr {
+            left: Box::new(Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Literal(Literal::Number(10.0))),
+                operator: BinaryOperator::Multiply,
+                right: Box::new(Expr::Literal(Literal::Number(3.0))),
+            })),
+            operator: BinaryOperator::Add,
+            right: Box::new(Expr::Unary(UnaryExpr {
+                operator: UnaryOperator::Negate,
+                right: Box::new(Expr::Literal(Literal::Number(5.0))),
+            })),
+        });
+        assert_eq!(result, vec![Stmt::MoveForward(MoveForwardStmt { distance: expected })]);
+    }
+
+    #[test]
+    fn unary_minus_starts_new_argument() {
+        let result = Parser::new_from_str("FD 10 -5").parse().unwrap();
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn comparison_binds_weaker_than_arithmetic() {
+        let result = Parser::new_from_str("IF 1 + 1 = 2 [ FD 10 ]").parse().unwrap();
+        match &result[0] {
+            Stmt::If(stmt) => match &stmt.condition {
+                Expr::Binary(expr) => assert_eq!(expr.operator, BinaryOperator::Equal),
+                other => panic!("Expected comparison but got {:?}", other),
+            },
+            other => panic!("Expected IF but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ifelse_requires_two_blocks() {
+        assert!(Parser::new_from_str("IFELSE 1 < 2 [ FD 10 ] [ BK 10 ]").parse().is_ok());
+        assert!(Parser::new_from_str("IFELSE 1 < 2 [ FD 10 ]").parse().is_err());
+    }
+
+    #[test]
+    fn parsing_for_loop_with_optional_step() {
+        let result = Parser::new_from_str("FOR [i 1 10] [ FD :i ] FOR [j 10 0 -2] [ FD :j ]").parse().unwrap();
+        match (&result[0], &result[1]) {
+            (Stmt::For(first), Stmt::For(second)) => {
+                assert_eq!(first.variable, "i");
+                assert_eq!(first.step, None);
+                assert_eq!(second.variable, "j");
+                assert!(second.step.is_some());
+            },
+            other => panic!("Expected two FOR loops but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parsing_list_literal() {
+        let result = Parser::new_from_str("MAKE \"list [10 -5 [Hello world] \"x]").parse().unwrap();
+        let expected = Literal::List(vec![
+            Literal::Number(10.0),
+            Literal::Number(-5.0),
+            Literal::List(vec![Literal::Word("Hello".into()), Literal::Word("world".into())]),
+            Literal::Word("\"x".into()),
+        ]);
+        match &result[0] {
+            Stmt::Make(stmt) => assert_eq!(stmt.value, Expr::Literal(expected)),
+            other => panic!("Expected MAKE but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn list_literal_keeps_words_as_typed() {
+        let result = Parser::new_from_str("PRINT [turn left and go fd to the END] PRINT [a -5 -b a-b (c)]").parse().unwrap();
+        let words = |words: &[&str]| Literal::List(words.iter().map(|x| list_item(x.to_string())).collect());
+        match &result[..] {
+            [Stmt::Print(keywords), Stmt::Print(minus)] => {
+                assert_eq!(keywords.value, Expr::Literal(words(&["turn", "left", "and", "go", "fd", "to", "the", "END"])));
+                assert_eq!(minus.value, Expr::Literal(words(&["a", "-5", "-b", "a-b", "(c)"])));
+            },
+            other => panic!("Expected two PRINT statements but got {:?}", other),
+        }
+        assert_eq!(list_item("-5".into()), Literal::Number(-5.0));
+        assert_eq!(list_item("inf".into()), Literal::Word("inf".into()));
+    }
+
+    #[test]
+    fn parsing_circle_as_full_arc() {
+        let result = Parser::new_from_str("CIRCLE 20").parse().unwrap();
+        assert_eq!(result[0], Stmt::Arc(ArcStmt {
+            angle: Expr::Literal(Literal::Number(360.0)),
+            radius: Expr::Literal(Literal::Number(20.0)),
+        }));
+    }
+
+    #[test]
+    fn parsing_stamp() {
+        let result = Parser::new_from_str("HT PU STAMP").parse().unwrap();
+        assert_eq!(result, vec![Stmt::HideTurtle, Stmt::PenUp, Stmt::Stamp]);
+    }
+
+    #[test]
+    fn parsing_filled_block() {
+        let result = Parser::new_from_str("FILLED #ff0000 [ FD 10 ]").parse().unwrap();
+        assert_eq!(result[0], Stmt::Filled(FilledStmt {
+            color: Expr::Literal(Literal::Color(Color::rgb(255, 0, 0))),
+            body: Box::new(Stmt::Block(vec![Stmt::MoveForward(MoveForwardStmt {
+                distance: Expr::Literal(Literal::Number(10.0)),
+            })])),
+        }));
+    }
+
+    #[test]
+    fn malformed_color_is_a_tokenizer_error() {
+        let result = Parser::new_from_str("FD 10\nCOLOR #12345g FD 10").parse();
+        assert!(matches!(result, Err(InterpreterError::Tokenize { line: 2, .. })));
+    }
+
+    #[test]
+    fn calling_unknown_procedure_is_an_error() {
+        assert!(Parser::new_from_str("FD 10 SPIRAL 5").parse().is_err());
+    }
+
+    #[test]
+    fn redefining_primitive_is_an_error() {
+        assert!(Parser::new_from_str("TO count :n FD :n END").parse().is_err());
+    }
+
+    #[test]
+    fn procedure_without_end_is_an_error() {
+        assert!(Parser::new_from_str("TO square FD 10").parse().is_err());
+    }
+}
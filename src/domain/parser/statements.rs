@@ -1,35 +1,217 @@
 use crate::domain::interpreter::visitor::{StmtVisitable, StmtVisitor};
+use crate::domain::geometry::BoundaryMode;
 use crate::domain::parser::expressions::Expr;
+use crate::domain::turtle::PenMode;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct RepeatStmt {
-    pub count: usize,
+    pub count: Expr,
     pub body: Box<Stmt>,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct WhileStmt {
+    // UNTIL is represented as WHILE with negated condition.
+    pub condition: Expr,
+    pub body: Box<Stmt>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ForeverStmt {
+    pub body: Box<Stmt>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ForStmt {
+    // Name of the loop variable, stored in lowercase.
+    pub variable: String,
+    pub start: Expr,
+    pub end: Expr,
+    // When missing, step is 1 or -1 depending on whether the loop counts up or down.
+    pub step: Option<Expr>,
+    pub body: Box<Stmt>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct IfStmt {
+    pub condition: Expr,
+    pub then_branch: Box<Stmt>,
+    // Only IFELSE has the else branch.
+    pub else_branch: Option<Box<Stmt>>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct SetColorStmt {
-    pub color: String,
+    pub color: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetBackgroundStmt {
+    pub color: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetPaletteStmt {
+    pub index: Expr,
+    pub color: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetPenSizeStmt {
+    pub size: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetLineCapStmt {
+    pub cap: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetLineJoinStmt {
+    pub join: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetDashStmt {
+    pub pattern: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FilledStmt {
+    pub color: Expr,
+    /// Path traced by the turtle while running the body is filled afterwards.
+    pub body: Box<Stmt>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LabelStmt {
+    pub text: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetLabelHeightStmt {
+    pub height: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetFontStmt {
+    pub font: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArcStmt {
+    /// Degrees clockwise from the turtle heading. CIRCLE is an ARC of 360 degrees.
+    pub angle: Expr,
+    pub radius: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetPositionStmt {
+    /// List of two numbers, e.g. `SETPOS [10 20]`.
+    pub position: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetXYStmt {
+    /// Coordinates that are left out keep their current value, so this covers SETXY, SETX and SETY.
+    pub x: Option<Expr>,
+    pub y: Option<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetHeadingStmt {
+    pub heading: Expr,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct MoveForwardStmt {
-    pub distance: f64,
+    pub distance: Expr,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct MoveBackwardStmt {
-    pub distance: f64,
+    pub distance: Expr,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct RotateRightStmt {
-    pub angular_distance: f64,
+    pub angular_distance: Expr,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct RotateLeftStmt {
-    pub angular_distance: f64,
+    pub angular_distance: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetShapeStmt {
+    /// Name of a built-in shape, or SVG path data, e.g. `SETSHAPE [M 0 -10 L 6 6 L -6 6 Z]`.
+    pub shape: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TellStmt {
+    /// Turtle number or name, or a list of them, e.g. `TELL [1 2 3]`.
+    pub turtles: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AskStmt {
+    pub turtles: Expr,
+    /// Runs with only the asked turtles active. Turtles picked by TELL are restored afterwards.
+    pub body: Box<Stmt>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MakeStmt {
+    // Name is stored in lowercase.
+    pub name: String,
+    pub value: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PrintStyle {
+    /// PRINT: lists are printed without the outer brackets, followed by a new line.
+    Print,
+    /// SHOW: lists are printed with brackets, followed by a new line.
+    Show,
+    /// TYPE: same as PRINT, but without the new line.
+    Type,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrintStmt {
+    pub value: Expr,
+    pub style: PrintStyle,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RerandomStmt {
+    pub seed: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetRecursionDepthStmt {
+    pub depth: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProcedureDeclarationStmt {
+    // Name and parameters are stored in lowercase. Parameters are stored without the leading colon.
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub body: Box<Stmt>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProcedureCallStmt {
+    pub name: String,
+    pub arguments: Vec<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct OutputStmt {
+    pub value: Expr,
 }
 
 // Statements do not result in a value. They are just evaluated.
@@ -38,24 +220,112 @@ pub enum Stmt {
     Block(Vec<Stmt>),
     Expression(Expr),
     Repeat(RepeatStmt),
+    While(WhileStmt),
+    Forever(ForeverStmt),
+    For(ForStmt),
+    If(IfStmt),
     SetColor(SetColorStmt),
+    SetPalette(SetPaletteStmt),
+    SetBackground(SetBackgroundStmt),
+    SetPenSize(SetPenSizeStmt),
+    SetLineCap(SetLineCapStmt),
+    SetLineJoin(SetLineJoinStmt),
+    SetDash(SetDashStmt),
     MoveForward(MoveForwardStmt),
     MoveBack(MoveBackwardStmt),
     RotateRight(RotateRightStmt),
     RotateLeft(RotateLeftStmt),
+    Arc(ArcStmt),
+    Filled(FilledStmt),
+    Fill,
+    Label(LabelStmt),
+    SetLabelHeight(SetLabelHeightStmt),
+    SetFont(SetFontStmt),
+    SetPosition(SetPositionStmt),
+    SetXY(SetXYStmt),
+    SetHeading(SetHeadingStmt),
+    Home,
+    Clean,
+    ClearScreen,
+    PenUp,
+    PenDown,
+    /// PENPAINT, PENERASE and PENREVERSE. They also put the pen down.
+    SetPenMode(PenMode),
+    ShowTurtle,
+    HideTurtle,
+    /// Draws the turtle shape onto the drawing.
+    Stamp,
+    /// PUSHTURTLE saves the whole turtle and POPTURTLE brings back the last saved one.
+    PushTurtle,
+    PopTurtle,
+    SetShape(SetShapeStmt),
+    /// WRAP, FENCE and WINDOW.
+    SetBoundaryMode(BoundaryMode),
+    Tell(TellStmt),
+    Ask(AskStmt),
+    Make(MakeStmt),
+    Rerandom(RerandomStmt),
+    SetRecursionDepth(SetRecursionDepthStmt),
+    Print(PrintStmt),
+    ProcedureDeclaration(ProcedureDeclarationStmt),
+    ProcedureCall(ProcedureCallStmt),
+    Stop,
+    Output(OutputStmt),
 }
 
 impl StmtVisitable for Stmt {
-    fn accept<V: StmtVisitor>(&mut self, visitor: &mut V) -> V::Result {
+    fn accept<V: StmtVisitor>(&self, visitor: &mut V) -> V::Result {
         match self {
             Stmt::Expression(expr) => visitor.visit_expression(expr),
             Stmt::Block(stmts) => visitor.visit_block(stmts),
             Stmt::Repeat(stmt) => visitor.visit_repeat(stmt),
+            Stmt::While(stmt) => visitor.visit_while(stmt),
+            Stmt::Forever(stmt) => visitor.visit_forever(stmt),
+            Stmt::For(stmt) => visitor.visit_for(stmt),
+            Stmt::If(stmt) => visitor.visit_if(stmt),
             Stmt::SetColor(stmt) => visitor.visit_set_color(stmt),
+            Stmt::SetPalette(stmt) => visitor.visit_set_palette(stmt),
+            Stmt::SetBackground(stmt) => visitor.visit_set_background(stmt),
+            Stmt::SetPenSize(stmt) => visitor.visit_set_pen_size(stmt),
+            Stmt::SetLineCap(stmt) => visitor.visit_set_line_cap(stmt),
+            Stmt::SetLineJoin(stmt) => visitor.visit_set_line_join(stmt),
+            Stmt::SetDash(stmt) => visitor.visit_set_dash(stmt),
             Stmt::MoveForward(stmt) => visitor.visit_move_forward(stmt),
             Stmt::MoveBack(stmt) => visitor.visit_move_backward(stmt),
             Stmt::RotateRight(stmt) => visitor.visit_rotate_right(stmt),
             Stmt::RotateLeft(stmt) => visitor.visit_rotate_left(stmt),
+            Stmt::Arc(stmt) => visitor.visit_arc(stmt),
+            Stmt::Filled(stmt) => visitor.visit_filled(stmt),
+            Stmt::Fill => visitor.visit_fill(),
+            Stmt::Label(stmt) => visitor.visit_label(stmt),
+            Stmt::SetLabelHeight(stmt) => visitor.visit_set_label_height(stmt),
+            Stmt::SetFont(stmt) => visitor.visit_set_font(stmt),
+            Stmt::SetPosition(stmt) => visitor.visit_set_position(stmt),
+            Stmt::SetXY(stmt) => visitor.visit_set_xy(stmt),
+            Stmt::SetHeading(stmt) => visitor.visit_set_heading(stmt),
+            Stmt::Home => visitor.visit_home(),
+            Stmt::Clean => visitor.visit_clean(),
+            Stmt::ClearScreen => visitor.visit_clear_screen(),
+            Stmt::PenUp => visitor.visit_pen_up(),
+            Stmt::PenDown => visitor.visit_pen_down(),
+            Stmt::SetPenMode(mode) => visitor.visit_set_pen_mode(mode),
+            Stmt::ShowTurtle => visitor.visit_show_turtle(),
+            Stmt::HideTurtle => visitor.visit_hide_turtle(),
+            Stmt::Stamp => visitor.visit_stamp(),
+            Stmt::PushTurtle => visitor.visit_push_turtle(),
+            Stmt::PopTurtle => visitor.visit_pop_turtle(),
+            Stmt::SetShape(stmt) => visitor.visit_set_shape(stmt),
+            Stmt::SetBoundaryMode(mode) => visitor.visit_set_boundary_mode(mode),
+            Stmt::Tell(stmt) => visitor.visit_tell(stmt),
+            Stmt::Ask(stmt) => visitor.visit_ask(stmt),
+            Stmt::Make(stmt) => visitor.visit_make(stmt),
+            Stmt::Rerandom(stmt) => visitor.visit_rerandom(stmt),
+            Stmt::SetRecursionDepth(stmt) => visitor.visit_set_recursion_depth(stmt),
+            Stmt::Print(stmt) => visitor.visit_print(stmt),
+            Stmt::ProcedureDeclaration(stmt) => visitor.visit_procedure_declaration(stmt),
+            Stmt::ProcedureCall(stmt) => visitor.visit_procedure_call(stmt),
+            Stmt::Stop => visitor.visit_stop(),
+            Stmt::Output(stmt) => visitor.visit_output(stmt),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,48 @@
+/// Small pseudo-random number generator (SplitMix64).
+/// We don't need cryptographic quality, but we need the same seed to always produce
+/// the same sequence so RERANDOM can make drawings reproducible.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random {
+            state: seed,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number from `0` to `bound - 1`. Bound must be positive.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::random::Random;
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+        for _ in 0..10 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+    }
+
+    #[test]
+    fn below_stays_within_bound() {
+        let mut random = Random::new(7);
+        assert!((0..1000).all(|_| random.below(6) < 6));
+    }
+}
@@ -1,10 +1,35 @@
+/// Token as it was typed in the source. Lists keep their words as typed, e.g. `[fd -b]`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Spelling {
+    pub text: String,
+    /// Whitespace or the start of the source comes right before the token.
+    pub separated: bool,
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub enum TokenType {
     LeftBracketToken,
     RightBracketToken,
+    LeftParenthesisToken,
+    RightParenthesisToken,
+
+    PlusToken,
+    MinusToken,
+    UnaryMinusToken,
+    StarToken,
+    SlashToken,
+    EqualToken,
+    NotEqualToken,
+    LessToken,
+    LessEqualToken,
+    GreaterToken,
+    GreaterEqualToken,
 
     NumberToken,
     ColorToken,
+    VariableToken,
+    IdentifierToken,
+    WordToken,
 
     SetColorCommandToken,
     ForwardCommandToken,
@@ -13,7 +38,65 @@ pub enum TokenType {
     TurnLeftCommandToken,
 
     RepeatLoopToken,
+    WhileLoopToken,
+    UntilLoopToken,
+    ForeverLoopToken,
+    ForLoopToken,
+
+    IfToken,
+    IfElseToken,
+    AndToken,
+    OrToken,
+    NotToken,
 
+    MakeToken,
+    RerandomToken,
+    PrintToken,
+    ShowToken,
+    TypeToken,
+    ToToken,
+    EndToken,
+    StopToken,
+    OutputToken,
+
+    PenUpToken,
+    PenDownToken,
+    PenPaintToken,
+    PenEraseToken,
+    PenReverseToken,
+    SetPenSizeToken,
+    SetLineCapToken,
+    SetLineJoinToken,
+    SetDashToken,
+    SetPosToken,
+    SetXYToken,
+    SetXToken,
+    SetYToken,
+    SetHeadingToken,
+    HomeToken,
+    CleanToken,
+    ClearScreenToken,
+    ArcToken,
+    CircleToken,
+    FilledToken,
+    FillToken,
+    LabelToken,
+    SetLabelHeightToken,
+    SetFontToken,
+    SetPaletteToken,
+    SetBackgroundToken,
+    TellToken,
+    AskToken,
+    WrapToken,
+    FenceToken,
+    WindowToken,
+    ShowTurtleToken,
+    HideTurtleToken,
+    SetShapeToken,
+    StampToken,
+    PushTurtleToken,
+    PopTurtleToken,
+    SetRecursionDepthToken,
     IllegalToken,
 }
 
@@ -21,9 +104,31 @@ pub enum TokenType {
 pub enum Token {
     LeftBracketToken(usize),
     RightBracketToken(usize),
+    LeftParenthesisToken(usize),
+    RightParenthesisToken(usize),
+
+    PlusToken(usize),
+    MinusToken(usize),
+    // Minus that negates the value that follows, e.g. `-5` in `SETXY 10 -5`.
+    UnaryMinusToken(usize),
+    StarToken(usize),
+    SlashToken(usize),
+    EqualToken(usize),
+    NotEqualToken(usize),
+    LessToken(usize),
+    LessEqualToken(usize),
+    GreaterToken(usize),
+    GreaterEqualToken(usize),
 
     NumberToken(usize, f64),
     ColorToken(usize, String),
+    // Variable reference such as `:size`. Name is stored without the colon.
+    VariableToken(usize, String),
+    // Any identifier that is not a keyword, e.g. a name of user-defined procedure.
+    // Name is stored in its original case.
+    IdentifierToken(usize, String),
+    // Quoted word such as `"size`. Word is stored without the quote.
+    WordToken(usize, String),
 
     SetColorCommandToken(usize),
     ForwardCommandToken(usize),
@@ -32,7 +137,65 @@ pub enum Token {
     TurnLeftCommandToken(usize),
 
     RepeatLoopToken(usize),
+    WhileLoopToken(usize),
+    UntilLoopToken(usize),
+    ForeverLoopToken(usize),
+    ForLoopToken(usize),
+
+    IfToken(usize),
+    IfElseToken(usize),
+    AndToken(usize),
+    OrToken(usize),
+    NotToken(usize),
+
+    MakeToken(usize),
+    RerandomToken(usize),
+    PrintToken(usize),
+    ShowToken(usize),
+    TypeToken(usize),
+    ToToken(usize),
+    EndToken(usize),
+    StopToken(usize),
+    OutputToken(usize),
 
+    PenUpToken(usize),
+    PenDownToken(usize),
+    PenPaintToken(usize),
+    PenEraseToken(usize),
+    PenReverseToken(usize),
+    SetPenSizeToken(usize),
+    SetLineCapToken(usize),
+    SetLineJoinToken(usize),
+    SetDashToken(usize),
+    SetPosToken(usize),
+    SetXYToken(usize),
+    SetXToken(usize),
+    SetYToken(usize),
+    SetHeadingToken(usize),
+    HomeToken(usize),
+    CleanToken(usize),
+    ClearScreenToken(usize),
+    ArcToken(usize),
+    CircleToken(usize),
+    FilledToken(usize),
+    FillToken(usize),
+    LabelToken(usize),
+    SetLabelHeightToken(usize),
+    SetFontToken(usize),
+    SetPaletteToken(usize),
+    SetBackgroundToken(usize),
+    TellToken(usize),
+    AskToken(usize),
+    WrapToken(usize),
+    FenceToken(usize),
+    WindowToken(usize),
+    ShowTurtleToken(usize),
+    HideTurtleToken(usize),
+    SetShapeToken(usize),
+    StampToken(usize),
+    PushTurtleToken(usize),
+    PopTurtleToken(usize),
+    SetRecursionDepthToken(usize),
     IllegalToken(usize, String),
 }
 
@@ -41,6 +204,20 @@ impl Token {
         match self {
             Token::LeftBracketToken(line) => *line,
             Token::RightBracketToken(line) => *line,
+            Token::LeftParenthesisToken(line) => *line,
+            Token::RightParenthesisToken(line) => *line,
+
+            Token::PlusToken(line) => *line,
+            Token::MinusToken(line) => *line,
+            Token::UnaryMinusToken(line) => *line,
+            Token::StarToken(line) => *line,
+            Token::SlashToken(line) => *line,
+            Token::EqualToken(line) => *line,
+            Token::NotEqualToken(line) => *line,
+            Token::LessToken(line) => *line,
+            Token::LessEqualToken(line) => *line,
+            Token::GreaterToken(line) => *line,
+            Token::GreaterEqualToken(line) => *line,
 
             // TODO: The number may not be the same as input since.
             // Example:
@@ -48,6 +225,9 @@ impl Token {
             //    Output string: 0.6000000000000001
             Token::NumberToken(line, _) => *line,
             Token::ColorToken(line, _) => *line,
+            Token::VariableToken(line, _) => *line,
+            Token::IdentifierToken(line, _) => *line,
+            Token::WordToken(line, _) => *line,
 
             Token::SetColorCommandToken(line) => *line,
             Token::ForwardCommandToken(line) => *line,
@@ -56,7 +236,65 @@ impl Token {
             Token::TurnLeftCommandToken(line) => *line,
 
             Token::RepeatLoopToken(line) => *line,
+            Token::WhileLoopToken(line) => *line,
+            Token::UntilLoopToken(line) => *line,
+            Token::ForeverLoopToken(line) => *line,
+            Token::ForLoopToken(line) => *line,
+
+            Token::IfToken(line) => *line,
+            Token::IfElseToken(line) => *line,
+            Token::AndToken(line) => *line,
+            Token::OrToken(line) => *line,
+            Token::NotToken(line) => *line,
+
+            Token::MakeToken(line) => *line,
+            Token::RerandomToken(line) => *line,
+            Token::PrintToken(line) => *line,
+            Token::ShowToken(line) => *line,
+            Token::TypeToken(line) => *line,
+            Token::ToToken(line) => *line,
+            Token::EndToken(line) => *line,
+            Token::StopToken(line) => *line,
+            Token::OutputToken(line) => *line,
 
+            Token::PenUpToken(line) => *line,
+            Token::PenDownToken(line) => *line,
+            Token::PenPaintToken(line) => *line,
+            Token::PenEraseToken(line) => *line,
+            Token::PenReverseToken(line) => *line,
+            Token::SetPenSizeToken(line) => *line,
+            Token::SetLineCapToken(line) => *line,
+            Token::SetLineJoinToken(line) => *line,
+            Token::SetDashToken(line) => *line,
+            Token::SetPosToken(line) => *line,
+            Token::SetXYToken(line) => *line,
+            Token::SetXToken(line) => *line,
+            Token::SetYToken(line) => *line,
+            Token::SetHeadingToken(line) => *line,
+            Token::HomeToken(line) => *line,
+            Token::CleanToken(line) => *line,
+            Token::ClearScreenToken(line) => *line,
+            Token::ArcToken(line) => *line,
+            Token::CircleToken(line) => *line,
+            Token::FilledToken(line) => *line,
+            Token::FillToken(line) => *line,
+            Token::LabelToken(line) => *line,
+            Token::SetLabelHeightToken(line) => *line,
+            Token::SetFontToken(line) => *line,
+            Token::SetPaletteToken(line) => *line,
+            Token::SetBackgroundToken(line) => *line,
+            Token::TellToken(line) => *line,
+            Token::AskToken(line) => *line,
+            Token::WrapToken(line) => *line,
+            Token::FenceToken(line) => *line,
+            Token::WindowToken(line) => *line,
+            Token::ShowTurtleToken(line) => *line,
+            Token::HideTurtleToken(line) => *line,
+            Token::SetShapeToken(line) => *line,
+            Token::StampToken(line) => *line,
+            Token::PushTurtleToken(line) => *line,
+            Token::PopTurtleToken(line) => *line,
+            Token::SetRecursionDepthToken(line) => *line,
             Token::IllegalToken(line, _) => *line,
         }
     }
@@ -65,6 +303,20 @@ impl Token {
         match self {
             Token::LeftBracketToken(_) => "[".to_string(),
             Token::RightBracketToken(_) => "]".to_string(),
+            Token::LeftParenthesisToken(_) => "(".to_string(),
+            Token::RightParenthesisToken(_) => ")".to_string(),
+
+            Token::PlusToken(_) => "+".to_string(),
+            Token::MinusToken(_) => "-".to_string(),
+            Token::UnaryMinusToken(_) => "-".to_string(),
+            Token::StarToken(_) => "*".to_string(),
+            Token::SlashToken(_) => "/".to_string(),
+            Token::EqualToken(_) => "=".to_string(),
+            Token::NotEqualToken(_) => "<>".to_string(),
+            Token::LessToken(_) => "<".to_string(),
+            Token::LessEqualToken(_) => "<=".to_string(),
+            Token::GreaterToken(_) => ">".to_string(),
+            Token::GreaterEqualToken(_) => ">=".to_string(),
 
             // TODO: The number may not be the same as input since.
             // Example:
@@ -72,6 +324,9 @@ impl Token {
             //    Output string: 0.6000000000000001
             Token::NumberToken(_, number) => number.to_string(),
             Token::ColorToken(_, number) => number.to_string(),
+            Token::VariableToken(_, name) => format!(":{}", name),
+            Token::IdentifierToken(_, name) => name.clone(),
+            Token::WordToken(_, word) => format!("\"{}", word),
 
             Token::SetColorCommandToken(_) => "COLOR".to_string(),
             Token::ForwardCommandToken(_) => "FD".to_string(),
@@ -80,7 +335,65 @@ impl Token {
             Token::TurnLeftCommandToken(_) => "LR".to_string(),
 
             Token::RepeatLoopToken(_) => "REPEAT".to_string(),
+            Token::WhileLoopToken(_) => "WHILE".to_string(),
+            Token::UntilLoopToken(_) => "UNTIL".to_string(),
+            Token::ForeverLoopToken(_) => "FOREVER".to_string(),
+            Token::ForLoopToken(_) => "FOR".to_string(),
+
+            Token::IfToken(_) => "IF".to_string(),
+            Token::IfElseToken(_) => "IFELSE".to_string(),
+            Token::AndToken(_) => "AND".to_string(),
+            Token::OrToken(_) => "OR".to_string(),
+            Token::NotToken(_) => "NOT".to_string(),
 
+            Token::MakeToken(_) => "MAKE".to_string(),
+            Token::RerandomToken(_) => "RERANDOM".to_string(),
+            Token::PrintToken(_) => "PRINT".to_string(),
+            Token::ShowToken(_) => "SHOW".to_string(),
+            Token::TypeToken(_) => "TYPE".to_string(),
+            Token::ToToken(_) => "TO".to_string(),
+            Token::EndToken(_) => "END".to_string(),
+            Token::StopToken(_) => "STOP".to_string(),
+            Token::OutputToken(_) => "OUTPUT".to_string(),
+
+            Token::PenUpToken(_) => "PENUP".to_string(),
+            Token::PenDownToken(_) => "PENDOWN".to_string(),
+            Token::PenPaintToken(_) => "PENPAINT".to_string(),
+            Token::PenEraseToken(_) => "PENERASE".to_string(),
+            Token::PenReverseToken(_) => "PENREVERSE".to_string(),
+            Token::SetPenSizeToken(_) => "SETPENSIZE".to_string(),
+            Token::SetLineCapToken(_) => "SETLINECAP".to_string(),
+            Token::SetLineJoinToken(_) => "SETLINEJOIN".to_string(),
+            Token::SetDashToken(_) => "SETDASH".to_string(),
+            Token::SetPosToken(_) => "SETPOS".to_string(),
+            Token::SetXYToken(_) => "SETXY".to_string(),
+            Token::SetXToken(_) => "SETX".to_string(),
+            Token::SetYToken(_) => "SETY".to_string(),
+            Token::SetHeadingToken(_) => "SETHEADING".to_string(),
+            Token::HomeToken(_) => "HOME".to_string(),
+            Token::CleanToken(_) => "CLEAN".to_string(),
+            Token::ClearScreenToken(_) => "CLEARSCREEN".to_string(),
+            Token::ArcToken(_) => "ARC".to_string(),
+            Token::CircleToken(_) => "CIRCLE".to_string(),
+            Token::FilledToken(_) => "FILLED".to_string(),
+            Token::FillToken(_) => "FILL".to_string(),
+            Token::LabelToken(_) => "LABEL".to_string(),
+            Token::SetLabelHeightToken(_) => "SETLABELHEIGHT".to_string(),
+            Token::SetFontToken(_) => "SETFONT".to_string(),
+            Token::SetPaletteToken(_) => "SETPALETTE".to_string(),
+            Token::SetBackgroundToken(_) => "SETBACKGROUND".to_string(),
+            Token::TellToken(_) => "TELL".to_string(),
+            Token::AskToken(_) => "ASK".to_string(),
+            Token::WrapToken(_) => "WRAP".to_string(),
+            Token::FenceToken(_) => "FENCE".to_string(),
+            Token::WindowToken(_) => "WINDOW".to_string(),
+            Token::ShowTurtleToken(_) => "SHOWTURTLE".to_string(),
+            Token::HideTurtleToken(_) => "HIDETURTLE".to_string(),
+            Token::SetShapeToken(_) => "SETSHAPE".to_string(),
+            Token::StampToken(_) => "STAMP".to_string(),
+            Token::PushTurtleToken(_) => "PUSHTURTLE".to_string(),
+            Token::PopTurtleToken(_) => "POPTURTLE".to_string(),
+            Token::SetRecursionDepthToken(_) => "SETRECURSIONDEPTH".to_string(),
             Token::IllegalToken(_, illegal) => illegal.clone(),
         }
     }
@@ -88,9 +401,26 @@ impl Token {
         match self {
             Token::LeftBracketToken(_) => TokenType::LeftBracketToken,
             Token::RightBracketToken(_) => TokenType::RightBracketToken,
+            Token::LeftParenthesisToken(_) => TokenType::LeftParenthesisToken,
+            Token::RightParenthesisToken(_) => TokenType::RightParenthesisToken,
+
+            Token::PlusToken(_) => TokenType::PlusToken,
+            Token::MinusToken(_) => TokenType::MinusToken,
+            Token::UnaryMinusToken(_) => TokenType::UnaryMinusToken,
+            Token::StarToken(_) => TokenType::StarToken,
+            Token::SlashToken(_) => TokenType::SlashToken,
+            Token::EqualToken(_) => TokenType::EqualToken,
+            Token::NotEqualToken(_) => TokenType::NotEqualToken,
+            Token::LessToken(_) => TokenType::LessToken,
+            Token::LessEqualToken(_) => TokenType::LessEqualToken,
+            Token::GreaterToken(_) => TokenType::GreaterToken,
+            Token::GreaterEqualToken(_) => TokenType::GreaterEqualToken,
 
             Token::NumberToken(_, _) => TokenType::NumberToken,
             Token::ColorToken(_, _) => TokenType::ColorToken,
+            Token::VariableToken(_, _) => TokenType::VariableToken,
+            Token::IdentifierToken(_, _) => TokenType::IdentifierToken,
+            Token::WordToken(_, _) => TokenType::WordToken,
 
             Token::SetColorCommandToken(_) => TokenType::SetColorCommandToken,
             Token::ForwardCommandToken(_) => TokenType::ForwardCommandToken,
@@ -99,7 +429,65 @@ impl Token {
             Token::TurnLeftCommandToken(_) => TokenType::TurnLeftCommandToken,
 
             Token::RepeatLoopToken(_) => TokenType::RepeatLoopToken,
+            Token::WhileLoopToken(_) => TokenType::WhileLoopToken,
+            Token::UntilLoopToken(_) => TokenType::UntilLoopToken,
+            Token::ForeverLoopToken(_) => TokenType::ForeverLoopToken,
+            Token::ForLoopToken(_) => TokenType::ForLoopToken,
+
+            Token::IfToken(_) => TokenType::IfToken,
+            Token::IfElseToken(_) => TokenType::IfElseToken,
+            Token::AndToken(_) => TokenType::AndToken,
+            Token::OrToken(_) => TokenType::OrToken,
+            Token::NotToken(_) => TokenType::NotToken,
+
+            Token::MakeToken(_) => TokenType::MakeToken,
+            Token::RerandomToken(_) => TokenType::RerandomToken,
+            Token::PrintToken(_) => TokenType::PrintToken,
+            Token::ShowToken(_) => TokenType::ShowToken,
+            Token::TypeToken(_) => TokenType::TypeToken,
+            Token::ToToken(_) => TokenType::ToToken,
+            Token::EndToken(_) => TokenType::EndToken,
+            Token::StopToken(_) => TokenType::StopToken,
+            Token::OutputToken(_) => TokenType::OutputToken,
 
+            Token::PenUpToken(_) => TokenType::PenUpToken,
+            Token::PenDownToken(_) => TokenType::PenDownToken,
+            Token::PenPaintToken(_) => TokenType::PenPaintToken,
+            Token::PenEraseToken(_) => TokenType::PenEraseToken,
+            Token::PenReverseToken(_) => TokenType::PenReverseToken,
+            Token::SetPenSizeToken(_) => TokenType::SetPenSizeToken,
+            Token::SetLineCapToken(_) => TokenType::SetLineCapToken,
+            Token::SetLineJoinToken(_) => TokenType::SetLineJoinToken,
+            Token::SetDashToken(_) => TokenType::SetDashToken,
+            Token::SetPosToken(_) => TokenType::SetPosToken,
+            Token::SetXYToken(_) => TokenType::SetXYToken,
+            Token::SetXToken(_) => TokenType::SetXToken,
+            Token::SetYToken(_) => TokenType::SetYToken,
+            Token::SetHeadingToken(_) => TokenType::SetHeadingToken,
+            Token::HomeToken(_) => TokenType::HomeToken,
+            Token::CleanToken(_) => TokenType::CleanToken,
+            Token::ClearScreenToken(_) => TokenType::ClearScreenToken,
+            Token::ArcToken(_) => TokenType::ArcToken,
+            Token::CircleToken(_) => TokenType::CircleToken,
+            Token::FilledToken(_) => TokenType::FilledToken,
+            Token::FillToken(_) => TokenType::FillToken,
+            Token::LabelToken(_) => TokenType::LabelToken,
+            Token::SetLabelHeightToken(_) => TokenType::SetLabelHeightToken,
+            Token::SetFontToken(_) => TokenType::SetFontToken,
+            Token::SetPaletteToken(_) => TokenType::SetPaletteToken,
+            Token::SetBackgroundToken(_) => TokenType::SetBackgroundToken,
+            Token::TellToken(_) => TokenType::TellToken,
+            Token::AskToken(_) => TokenType::AskToken,
+            Token::WrapToken(_) => TokenType::WrapToken,
+            Token::FenceToken(_) => TokenType::FenceToken,
+            Token::WindowToken(_) => TokenType::WindowToken,
+            Token::ShowTurtleToken(_) => TokenType::ShowTurtleToken,
+            Token::HideTurtleToken(_) => TokenType::HideTurtleToken,
+            Token::SetShapeToken(_) => TokenType::SetShapeToken,
+            Token::StampToken(_) => TokenType::StampToken,
+            Token::PushTurtleToken(_) => TokenType::PushTurtleToken,
+            Token::PopTurtleToken(_) => TokenType::PopTurtleToken,
+            Token::SetRecursionDepthToken(_) => TokenType::SetRecursionDepthToken,
             Token::IllegalToken(_, _) => TokenType::IllegalToken,
         }
     }
@@ -1,12 +1,24 @@
 use std::iter::Peekable;
 use std::str::Chars;
+use crate::domain::color::Color;
 use crate::domain::errors::InterpreterError;
-use crate::domain::tokenizer::token::Token;
+use crate::domain::tokenizer::token::{Spelling, Token};
 use phf::phf_map;
 
 static KEYWORD_TO_TOKEN: phf::Map<&'static str, fn(usize) -> Token> = phf_map! {
     "repeat" => |line| Token::RepeatLoopToken(line),
+    "while" => |line| Token::WhileLoopToken(line),
+    "until" => |line| Token::UntilLoopToken(line),
+    "forever" => |line| Token::ForeverLoopToken(line),
+    "for" => |line| Token::ForLoopToken(line),
+    "if" => |line| Token::IfToken(line),
+    "ifelse" => |line| Token::IfElseToken(line),
+    "and" => |line| Token::AndToken(line),
+    "or" => |line| Token::OrToken(line),
+    "not" => |line| Token::NotToken(line),
     "color" => |line| Token::SetColorCommandToken(line),
+    "setpencolor" => |line| Token::SetColorCommandToken(line),
+    "setpc" => |line| Token::SetColorCommandToken(line),
     "forward" => |line| Token::ForwardCommandToken(line),
     "fd" => |line| Token::ForwardCommandToken(line),
     "back" => |line| Token::BackwardCommandToken(line),
@@ -15,11 +27,78 @@ static KEYWORD_TO_TOKEN: phf::Map<&'static str, fn(usize) -> Token> = phf_map! {
     "lt" => |line| Token::TurnLeftCommandToken(line),
     "right" => |line| Token::TurnRightCommandToken(line),
     "rt" => |line| Token::TurnRightCommandToken(line),
+    "penup" => |line| Token::PenUpToken(line),
+    "pu" => |line| Token::PenUpToken(line),
+    "pendown" => |line| Token::PenDownToken(line),
+    "pd" => |line| Token::PenDownToken(line),
+    "penpaint" => |line| Token::PenPaintToken(line),
+    "ppt" => |line| Token::PenPaintToken(line),
+    "penerase" => |line| Token::PenEraseToken(line),
+    "pe" => |line| Token::PenEraseToken(line),
+    "penreverse" => |line| Token::PenReverseToken(line),
+    "px" => |line| Token::PenReverseToken(line),
+    "setpensize" => |line| Token::SetPenSizeToken(line),
+    "setlinecap" => |line| Token::SetLineCapToken(line),
+    "setlinejoin" => |line| Token::SetLineJoinToken(line),
+    "setdash" => |line| Token::SetDashToken(line),
+    "setpos" => |line| Token::SetPosToken(line),
+    "setxy" => |line| Token::SetXYToken(line),
+    "setx" => |line| Token::SetXToken(line),
+    "sety" => |line| Token::SetYToken(line),
+    "setheading" => |line| Token::SetHeadingToken(line),
+    "seth" => |line| Token::SetHeadingToken(line),
+    "home" => |line| Token::HomeToken(line),
+    "clean" => |line| Token::CleanToken(line),
+    "clearscreen" => |line| Token::ClearScreenToken(line),
+    "cs" => |line| Token::ClearScreenToken(line),
+    "arc" => |line| Token::ArcToken(line),
+    "circle" => |line| Token::CircleToken(line),
+    "filled" => |line| Token::FilledToken(line),
+    "fill" => |line| Token::FillToken(line),
+    "label" => |line| Token::LabelToken(line),
+    "setlabelheight" => |line| Token::SetLabelHeightToken(line),
+    "setfont" => |line| Token::SetFontToken(line),
+    "setpalette" => |line| Token::SetPaletteToken(line),
+    "setbackground" => |line| Token::SetBackgroundToken(line),
+    "setbg" => |line| Token::SetBackgroundToken(line),
+    "tell" => |line| Token::TellToken(line),
+    "ask" => |line| Token::AskToken(line),
+    "wrap" => |line| Token::WrapToken(line),
+    "fence" => |line| Token::FenceToken(line),
+    "window" => |line| Token::WindowToken(line),
+    "showturtle" => |line| Token::ShowTurtleToken(line),
+    "st" => |line| Token::ShowTurtleToken(line),
+    "hideturtle" => |line| Token::HideTurtleToken(line),
+    "ht" => |line| Token::HideTurtleToken(line),
+    "setshape" => |line| Token::SetShapeToken(line),
+    "stamp" => |line| Token::StampToken(line),
+    "pushturtle" => |line| Token::PushTurtleToken(line),
+    "savestate" => |line| Token::PushTurtleToken(line),
+    "popturtle" => |line| Token::PopTurtleToken(line),
+    "restorestate" => |line| Token::PopTurtleToken(line),
+    "setrecursiondepth" => |line| Token::SetRecursionDepthToken(line),
+    "make" => |line| Token::MakeToken(line),
+    "rerandom" => |line| Token::RerandomToken(line),
+    "print" => |line| Token::PrintToken(line),
+    "pr" => |line| Token::PrintToken(line),
+    "show" => |line| Token::ShowToken(line),
+    "type" => |line| Token::TypeToken(line),
+    "to" => |line| Token::ToToken(line),
+    "end" => |line| Token::EndToken(line),
+    "stop" => |line| Token::StopToken(line),
+    "output" => |line| Token::OutputToken(line),
+    "op" => |line| Token::OutputToken(line),
 };
 
 pub struct Tokenizer<'a> {
     source: Peekable<Chars<'a>>,
     line: usize,
+    // Last consumed character. Used to tell a unary minus from a binary one.
+    previous_character: Option<char>,
+    // Characters of the token being read.
+    spelling: String,
+    // Spelling of each token read so far, in the same order as the tokens.
+    spellings: Vec<Spelling>,
     errors: Vec<InterpreterError>
 }
 
@@ -31,10 +110,22 @@ impl<'a> Tokenizer<'a> {
         Tokenizer {
             source: source.chars().into_iter().peekable(),
             line: 1,
+            previous_character: None,
+            spelling: String::new(),
+            spellings: vec!(),
             errors: vec!(),
         }
     }
 
+    /// Errors found so far. Tokenizing continues after an error, so all of them are collected.
+    pub fn errors(&self) -> &[InterpreterError] {
+        &self.errors
+    }
+
+    pub fn spellings(&self) -> &[Spelling] {
+        &self.spellings
+    }
+
     #[cfg(test)]
     pub fn read_tokens(&mut self) -> Vec<Token> {
         self.into_iter().collect()
@@ -45,16 +136,43 @@ impl<'a> Iterator for Tokenizer<'a> {
     type Item = Token;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let character = self.source.next()?;
+        let previous_character = self.previous_character;
+        let separated = match previous_character {
+            None => true,
+            Some(x) => x.is_whitespace(),
+        };
+        self.spelling.clear();
+        let character = self.advance()?;
         let token = match character {
             ' ' | '\t' | '\r' => return self.next(),
             '\n' => {
                 self.line += 1;
                 return self.next();
             }
+            ';' => {
+                self.skip_comment();
+                return self.next();
+            }
+            '~' if self.is_line_continuation() => {
+                self.skip_line_continuation();
+                return self.next();
+            }
+            // Anywhere else tilde is an ordinary character, same as in UCBLogo.
+            '~' => self.consume_identifier('~'),
             '[' => Token::LeftBracketToken(self.line),
             ']' => Token::RightBracketToken(self.line),
+            '(' => Token::LeftParenthesisToken(self.line),
+            ')' => Token::RightParenthesisToken(self.line),
+            '+' => Token::PlusToken(self.line),
+            '-' => self.consume_minus(previous_character),
+            '*' => Token::StarToken(self.line),
+            '/' => Token::SlashToken(self.line),
+            '=' => Token::EqualToken(self.line),
+            '<' => self.consume_less_than(),
+            '>' => self.consume_greater_than(),
             current if current == '#' => self.consume_color(current),
+            ':' => self.consume_variable(),
+            '"' => self.consume_word(),
             current if current.is_ascii_digit() => self.consume_number(current),
             current if is_alpha(&current) => self.consume_identifier(current),
             current => {
@@ -65,6 +183,7 @@ impl<'a> Iterator for Tokenizer<'a> {
                 Token::IllegalToken(self.line, current.to_string())
             }
         };
+        self.spellings.push(Spelling { text: self.spelling.clone(), separated });
         Some(token)
     }
 }
@@ -73,6 +192,22 @@ fn is_alpha(character: &char) -> bool {
     character.is_ascii_alphabetic() || *character == '_'
 }
 
+fn is_alphanumeric(character: &char) -> bool {
+    character.is_alphanumeric() || *character == '_'
+}
+
+fn continues_line(rest: impl Iterator<Item = char>) -> bool {
+    // Tilde continues the instruction on the next line only when nothing but whitespace follows it.
+    for character in rest {
+        match character {
+            ' ' | '\t' | '\r' => continue,
+            '\n' => return true,
+            _ => return false,
+        }
+    }
+    true
+}
+
 impl<'a> Tokenizer<'a> {
     /*
     Implements mechanics for scanning individual characters in the source code sequence.
@@ -93,7 +228,73 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn advance(&mut self) -> Option<char> {
-        self.source.next().take()
+        self.previous_character = self.source.next().take();
+        if let Some(character) = self.previous_character {
+            self.spelling.push(character);
+        }
+        self.previous_character
+    }
+
+    fn skip_comment(&mut self) {
+        // Comment spans until the end of the line. The new line itself is left for `next`
+        // so that line counting stays in one place.
+        while self.peek_satisfies(|x| *x != '\n') {
+            self.advance();
+        }
+    }
+
+    fn is_line_continuation(&mut self) -> bool {
+        continues_line(self.source.clone())
+    }
+
+    fn peek_is_tilde_in_word(&mut self) -> bool {
+        let mut rest = self.source.clone();
+        rest.next() == Some('~') && !continues_line(rest)
+    }
+
+    fn skip_line_continuation(&mut self) {
+        while self.peek_satisfies(|x| *x != '\n') {
+            self.advance();
+        }
+        if self.advance().is_some() {
+            self.line += 1;
+        }
+    }
+
+    fn consume_less_than(&mut self) -> Token {
+        if self.peek_satisfies(|x| *x == '=') {
+            self.advance();
+            Token::LessEqualToken(self.line)
+        } else if self.peek_satisfies(|x| *x == '>') {
+            self.advance();
+            Token::NotEqualToken(self.line)
+        } else {
+            Token::LessToken(self.line)
+        }
+    }
+
+    fn consume_greater_than(&mut self) -> Token {
+        if self.peek_satisfies(|x| *x == '=') {
+            self.advance();
+            Token::GreaterEqualToken(self.line)
+        } else {
+            Token::GreaterToken(self.line)
+        }
+    }
+
+    fn consume_minus(&mut self, previous_character: Option<char>) -> Token {
+        // Same rule as in UCBLogo: minus preceded by a space (or an opening bracket)
+        // and followed by a non-space is a unary minus. This makes `SETXY 10 -5` take two inputs,
+        // while `10 - 5` and `10-5` are still subtractions.
+        let follows_separator = match previous_character {
+            None => true,
+            Some(x) => x.is_whitespace() || x == '[' || x == '(',
+        };
+        if follows_separator && self.peek_satisfies(|x| !x.is_whitespace()) {
+            Token::UnaryMinusToken(self.line)
+        } else {
+            Token::MinusToken(self.line)
+        }
     }
 
     fn consume_number(&mut self, first_character: char) -> Token {
@@ -124,34 +325,61 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
-    fn consume_identifier(&mut self, first: char) -> Token {
-        let mut identifier_chars = vec![first];
-        while self.peek_satisfies(|x| x.is_alphanumeric()) {
-            identifier_chars.push(self.advance().unwrap());
+    fn read_name(&mut self, first: char) -> String {
+        let mut name_chars = vec![first];
+        while self.peek_satisfies(is_alphanumeric) || self.peek_is_tilde_in_word() {
+            name_chars.push(self.advance().unwrap());
         }
-        let identifier_as_str: String = identifier_chars.iter().collect();
+        name_chars.iter().collect()
+    }
+
+    fn consume_identifier(&mut self, first: char) -> Token {
+        let identifier_as_str = self.read_name(first);
         match KEYWORD_TO_TOKEN.get(&identifier_as_str.to_ascii_lowercase()) {
             Some(keyword_token) => keyword_token(self.line),
-            None => Token::IllegalToken(
-                self.line,
-                format!("The token is expected to be identifier. Identifier must be a keyword (functions, classes, and variables are not supported). Token `{}` does not match any keyword.", &identifier_as_str)
-            ),
+            // Original case is kept because identifiers inside lists are data, e.g. `[Hello World]`.
+            None => Token::IdentifierToken(self.line, identifier_as_str),
+        }
+    }
+
+    fn consume_variable(&mut self) -> Token {
+        // The colon has already been consumed. Variable name has to follow immediately.
+        if !self.peek_satisfies(is_alpha) {
+            self.errors.push(InterpreterError::Tokenize {
+                line: self.line,
+                message: "Expected variable name after colon (`:`).".into(),
+            });
+            return Token::IllegalToken(self.line, ":".into());
         }
+        let first = self.advance().unwrap();
+        Token::VariableToken(self.line, self.read_name(first).to_ascii_lowercase())
+    }
+
+    fn consume_word(&mut self) -> Token {
+        // The quote has already been consumed. Word spans until a whitespace, a bracket, a parenthesis or a comment.
+        // Same as in UCBLogo, so `(WORD "a "b)` ends the word before the closing parenthesis.
+        let mut word_chars = vec![];
+        while self.peek_satisfies(|x| !x.is_whitespace() && !matches!(x, '[' | ']' | '(' | ')' | ';')) {
+            word_chars.push(self.advance().unwrap());
+        }
+        Token::WordToken(self.line, word_chars.iter().collect())
     }
 
     pub fn consume_color(&mut self, first_character: char) -> Token {
+        // Everything alphanumeric belongs to the colour, so `#12345g` is reported instead of split in two tokens.
         let mut parts = vec![first_character];
-        for _ in 0..=5 {
-            if self.peek_satisfies(|x| x.is_ascii_hexdigit() ){
-                parts.push(self.advance().unwrap())
-            } else {
-                // TODO
-            }
+        while self.peek_satisfies(|x| x.is_ascii_alphanumeric()) {
+            parts.push(self.advance().unwrap())
+        }
+        let color: String = parts.iter().collect();
+        if Color::from_hex(&color).is_none() {
+            self.errors.push(InterpreterError::Tokenize {
+                line: self.line,
+                message: format!("Malformed color {}. Expected #RGB, #RGBA, #RRGGBB or #RRGGBBAA.", color),
+            });
+            return Token::IllegalToken(self.line, color);
         }
-        Token::ColorToken(
-            self.line,
-            parts.iter().collect()
-        )
+        Token::ColorToken(self.line, color)
     }
 }
 
@@ -201,5 +429,116 @@ mod tests {
         let r = tokenizer.read_tokens();
         assert_eq!(r[1].clone(), Token::ColorToken(1, "#004355".into()));
     }
+
+    #[test]
+    fn consuming_colors_with_alpha() {
+        let mut tokenizer = Tokenizer::new("#fff #ffff #FF000080".into());
+        let r = tokenizer.read_tokens();
+        assert_eq!(r[0].clone(), Token::ColorToken(1, "#fff".into()));
+        assert_eq!(r[1].clone(), Token::ColorToken(1, "#ffff".into()));
+        assert_eq!(r[2].clone(), Token::ColorToken(1, "#FF000080".into()));
+        assert!(tokenizer.errors().is_empty());
+    }
+
+    #[test]
+    fn malformed_color_is_an_error() {
+        for code in ["COLOR #12345", "COLOR #12345g", "COLOR #", "COLOR #ff00ff00ff"] {
+            let mut tokenizer = Tokenizer::new(code.into());
+            let r = tokenizer.read_tokens();
+            assert!(matches!(r[1], Token::IllegalToken(1, _)), "{}", code);
+            assert_eq!(tokenizer.errors().len(), 1, "{}", code);
+        }
+    }
+
+    #[test]
+    fn consuming_procedure_definition() {
+        let mut tokenizer = Tokenizer::new("TO Square :Size\nFD :size\nEND\nsquare 10".into());
+        let r = tokenizer.read_tokens();
+        assert_eq!(r[0].clone(), Token::ToToken(1));
+        assert_eq!(r[1].clone(), Token::IdentifierToken(1, "Square".into()));
+        assert_eq!(r[2].clone(), Token::VariableToken(1, "size".into()));
+        assert_eq!(r[4].clone(), Token::VariableToken(2, "size".into()));
+        assert_eq!(r[5].clone(), Token::EndToken(3));
+        assert_eq!(r[6].clone(), Token::IdentifierToken(4, "square".into()));
+    }
+
+    #[test]
+    fn skipping_comments() {
+        let mut tokenizer = Tokenizer::new("FD 10 ; move [ ahead ]\n; whole line\nRT \"a;b".into());
+        let r = tokenizer.read_tokens();
+        assert_eq!(r, vec![
+            Token::ForwardCommandToken(1),
+            Token::NumberToken(1, 10.0_f64),
+            Token::TurnRightCommandToken(3),
+            Token::WordToken(3, "a".into()),
+        ]);
+    }
+
+    #[test]
+    fn continuing_lines() {
+        let mut tokenizer = Tokenizer::new("REPEAT 4 ~\n[FD 10] ~  \r\nRT 90\nBK ~5 a~b c~\nd".into());
+        let r = tokenizer.read_tokens();
+        assert_eq!(r[2].clone(), Token::LeftBracketToken(2));
+        assert_eq!(r[6].clone(), Token::TurnRightCommandToken(3));
+        assert_eq!(r[8].clone(), Token::BackwardCommandToken(4));
+        // Tilde in the middle of a line is an ordinary character.
+        assert_eq!(r[9].clone(), Token::IdentifierToken(4, "~5".into()));
+        assert_eq!(r[10].clone(), Token::IdentifierToken(4, "a~b".into()));
+        assert_eq!(r[11].clone(), Token::IdentifierToken(4, "c".into()));
+        assert_eq!(r[12].clone(), Token::IdentifierToken(5, "d".into()));
+        assert!(tokenizer.errors.is_empty());
+    }
+
+    #[test]
+    fn consuming_minus() {
+        let mut tokenizer = Tokenizer::new("10 -5 10 - 5 10-5 (-5)".into());
+        let r = tokenizer.read_tokens();
+        assert_eq!(r[1].clone(), Token::UnaryMinusToken(1));
+        assert_eq!(r[4].clone(), Token::MinusToken(1));
+        assert_eq!(r[7].clone(), Token::MinusToken(1));
+        assert_eq!(r[10].clone(), Token::UnaryMinusToken(1));
+    }
+
+    #[test]
+    fn consuming_comparison_operators() {
+        let mut tokenizer = Tokenizer::new("= < > <= >= <>".into());
+        let r = tokenizer.read_tokens();
+        assert_eq!(r, vec![
+            Token::EqualToken(1),
+            Token::LessToken(1),
+            Token::GreaterToken(1),
+            Token::LessEqualToken(1),
+            Token::GreaterEqualToken(1),
+            Token::NotEqualToken(1),
+        ]);
+    }
+
+    #[test]
+    fn keeping_spelling() {
+        let mut tokenizer = Tokenizer::new("fd [a -5]\n  Rt".into());
+        tokenizer.read_tokens();
+        let spellings: Vec<(&str, bool)> = tokenizer.spellings().iter().map(|x| (x.text.as_str(), x.separated)).collect();
+        assert_eq!(spellings, vec![
+            ("fd", true), ("[", true), ("a", false), ("-", true), ("5", false), ("]", false), ("Rt", true),
+        ]);
+    }
+
+    #[test]
+    fn consuming_word() {
+        let mut tokenizer = Tokenizer::new("MAKE \"Size 50".into());
+        let r = tokenizer.read_tokens();
+        assert_eq!(r[0].clone(), Token::MakeToken(1));
+        assert_eq!(r[1].clone(), Token::WordToken(1, "Size".into()));
+        assert_eq!(r[2].clone(), Token::NumberToken(1, 50.0_f64));
+    }
+
+    #[test]
+    fn word_ends_at_parenthesis() {
+        let mut tokenizer = Tokenizer::new("(WORD \"a \"b)".into());
+        let r = tokenizer.read_tokens();
+        assert_eq!(r[2].clone(), Token::WordToken(1, "a".into()));
+        assert_eq!(r[3].clone(), Token::WordToken(1, "b".into()));
+        assert_eq!(r[4].clone(), Token::RightParenthesisToken(1));
+    }
 }
 
@@ -1,23 +1,184 @@
-use std::borrow::Cow;
-use crate::domain::geometry::Size2d;
+use crate::domain::color::Color;
+use crate::domain::geometry::{sin_cos_degrees, Location2d};
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PenMode {
+    /// Draws with the pen color.
+    Paint,
+    /// Erases whatever is under the line.
+    Erase,
+    /// Inverts pixels under the line, so drawing the same line twice removes it.
+    Reverse,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    pub fn from_name(name: &str) -> Option<LineCap> {
+        match name.to_ascii_lowercase().as_str() {
+            "butt" => Some(LineCap::Butt),
+            "round" => Some(LineCap::Round),
+            "square" => Some(LineCap::Square),
+            _ => None,
+        }
+    }
+
+    /// Value understood by the canvas `lineCap` property.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    pub fn from_name(name: &str) -> Option<LineJoin> {
+        match name.to_ascii_lowercase().as_str() {
+            "miter" => Some(LineJoin::Miter),
+            "round" => Some(LineJoin::Round),
+            "bevel" => Some(LineJoin::Bevel),
+            _ => None,
+        }
+    }
+
+    /// Value understood by the canvas `lineJoin` property.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        }
+    }
+}
+
+// Turtle seen from above, facing up. Its centre is at (50, 50).
+const TURTLE_PATH: &str = "M42.706,63.979C41.467,64.827 39.973,64.849 39.22,63.974C38.393,63.011 38.773,61.324 40.07,60.209C40.177,60.117 40.286,60.032 40.398,59.954C38.743,55.63 38.934,51.169 40.952,46.921C40.46,46.548 40.022,46.002 39.723,45.331C39,43.712 39.355,41.978 40.515,41.46C41.409,41.061 42.522,41.501 43.316,42.465C44.181,40.961 45.28,39.376 46,39.54C45.537,38.56 45.285,38.494 45.26,37.41C45.262,34.996 46.368,33.71 48.26,32.21C49.26,31.412 50.69,31.412 51.69,32.21C53.573,33.717 54.676,34.998 54.69,37.41C54.693,38.487 54.472,38.553 54.04,39.54C54.745,39.379 55.804,40.898 56.65,42.374C57.646,41.38 58.992,41.066 59.882,41.699C60.917,42.435 60.944,44.176 59.943,45.583C59.643,46.005 59.284,46.35 58.9,46.606C61.048,50.889 61.326,55.404 59.712,59.791C60.044,60.047 60.348,60.376 60.601,60.767C61.519,62.19 61.398,63.904 60.331,64.593C59.472,65.147 58.26,64.869 57.341,63.998C56.498,64.994 55.264,66.263 54.21,66.59C53.138,66.922 51.435,67.563 49.82,67.553C48.301,67.544 46.869,66.903 45.86,66.59C44.798,66.261 43.552,64.979 42.706,63.979Z";
+
+// Arrowhead facing up, centred on the origin.
+const TRIANGLE_PATH: &str = "M0,-12L9,9L0,4L-9,9Z";
+
+/// Sprite drawn on the turtle's location, turned towards its heading.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TurtleShape {
+    Turtle,
+    Triangle,
+    /// SVG path data set by SETSHAPE. It should face up, with the turtle's location at the origin.
+    Path(String),
+}
+
+impl TurtleShape {
+    pub fn from_name(name: &str) -> Option<TurtleShape> {
+        match name.to_ascii_lowercase().as_str() {
+            "turtle" => Some(TurtleShape::Turtle),
+            "triangle" => Some(TurtleShape::Triangle),
+            _ => None,
+        }
+    }
+
+    /// SVG path data understood by the canvas `Path2D` constructor.
+    pub fn path_data(&self) -> &str {
+        match self {
+            TurtleShape::Turtle => TURTLE_PATH,
+            TurtleShape::Triangle => TRIANGLE_PATH,
+            TurtleShape::Path(data) => data,
+        }
+    }
+
+    /// Point of the path placed on the turtle's location. The shape turns around it.
+    pub fn origin(&self) -> Location2d {
+        match self {
+            TurtleShape::Turtle => Location2d { x: 50.0, y: 50.0 },
+            _ => Location2d { x: 0.0, y: 0.0 },
+        }
+    }
+}
+
+/// Turtle state in Logo coordinates: origin in the centre of the canvas and y pointing up.
+/// Conversion to canvas pixels is done by `CanvasTransform` when drawing.
 #[derive(PartialEq, Clone)]
 pub struct Turtle {
     pub x: f64,
     pub y: f64,
-    // Angle in rad.
-    pub phi: f64,
+    // Degrees clockwise from north, in range [0, 360).
+    pub heading: f64,
 
-    pub color: Cow<'static, str>,
+    pub color: Color,
+    pub pen_down: bool,
+    pub pen_mode: PenMode,
+    pub pen_size: f64,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+    // Lengths of alternating dashes and gaps. Empty means a solid line.
+    pub dash: Vec<f64>,
+    // Text height in pixels used by LABEL.
+    pub label_height: f64,
+    // CSS font family used by LABEL.
+    pub font: String,
+    // Set by SHOWTURTLE and HIDETURTLE.
+    pub visible: bool,
+    pub shape: TurtleShape,
 }
 
-impl Turtle {
-    pub fn new_for_canvas(canvas_size: &Size2d) -> Turtle {
+impl Default for Turtle {
+    /// Turtle at home: in the centre, facing north.
+    fn default() -> Turtle {
         Turtle {
-            x: canvas_size.width / 2.0,
-            y: canvas_size.height / 2.0,
-            phi: std::f64::consts::PI / 2.0,
-            color: "#000000".into(),
+            x: 0.0,
+            y: 0.0,
+            heading: 0.0,
+            color: Color::BLACK,
+            pen_down: true,
+            pen_mode: PenMode::Paint,
+            pen_size: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            dash: Vec::new(),
+            label_height: 12.0,
+            font: "sans-serif".into(),
+            visible: true,
+            shape: TurtleShape::Turtle,
+        }
+    }
+}
+
+impl Turtle {
+    pub fn location(&self) -> Location2d {
+        Location2d { x: self.x, y: self.y }
+    }
+
+    pub fn set_heading(&mut self, heading: f64) {
+        self.heading = heading.rem_euclid(360.0);
+    }
+
+    /// Location after moving `distance` steps along the current heading.
+    pub fn ahead(&self, distance: f64) -> Location2d {
+        let (sin, cos) = sin_cos_degrees(self.heading);
+        Location2d {
+            x: self.x + distance * sin,
+            y: self.y + distance * cos,
         }
     }
+
+    /// Heading the turtle would need to face the target.
+    pub fn towards(&self, target: &Location2d) -> f64 {
+        (target.x - self.x).atan2(target.y - self.y).to_degrees().rem_euclid(360.0)
+    }
+
+    pub fn distance(&self, target: &Location2d) -> f64 {
+        (target.x - self.x).hypot(target.y - self.y)
+    }
 }
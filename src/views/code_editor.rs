@@ -28,17 +28,24 @@ fn run_interpreter(code: &str) -> Result<String, String> {
     let canvas_size =
         &get_canvas_size()
         .expect("Could not get a canvas size. Is the canvas ready?");
-    let turtle = Turtle::new_for_canvas(canvas_size);
+    let turtle = Turtle::default();
 
     let code_block = Parser::new_from_str(code).parse();
     if code_block.is_err() {
         let error_message = code_block.err().unwrap().to_string();
         return Err(error_message);
     }
-    let mut interpreter = Interpreter::new(turtle);
-    match interpreter.interpret_script(&mut code_block.unwrap()){
-        Ok(_) => Ok("✅ Done!".to_string()),
-        Err(error) => Err(error.to_string())
+    let mut interpreter = Interpreter::new(turtle, canvas_size.clone());
+    // Every run gets a different random sequence unless the script uses RERANDOM.
+    if let Some(performance) = web_sys::window().and_then(|x| x.performance()) {
+        interpreter.set_random_seed((performance.now() * 1000.0) as u64);
+    }
+    let result = interpreter.interpret_script(&code_block.unwrap());
+    // Output printed before an error is still shown, it helps with debugging.
+    let output = interpreter.output();
+    match result {
+        Ok(_) => Ok(format!("{}✅ Done!", output)),
+        Err(error) => Err(format!("{}{}", output, error))
     }
 }
 
@@ -167,6 +174,7 @@ pub fn CodeEditorElement(props: &CodeEditorElementProps) -> Html {
                             classes!(
                                 "textarea",
                                 "textarea-code",
+                                "console-output",
                                 (*has_run_resulted_in_error_handle).clone().then(|| Some("is-danger"))
                             )
                         }>
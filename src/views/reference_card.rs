@@ -47,15 +47,235 @@ fn ReferenceElement() -> Html {
                 </tr>
 
                 <tr>
-                    <td>{"COLOR <hex color>"}</td>
-                    <td>{"COLOR #663399"}</td>
-                    <td>{"Set color for the line."}</td>
+                    <td>{"COLOR <color>"}<br/>{"SETPENCOLOR"}<br/>{"SETPC"}</td>
+                    <td>{"COLOR #663399"}<br/>{"COLOR \"tomato"}<br/>{"COLOR [255 99 71]"}</td>
+                    <td>{"Set color for the line. Color is a hex color (#RGB, #RGBA, #RRGGBB, #RRGGBBAA), a CSS color name, a list of red, green, blue and optional alpha from 0 to 255, or a palette index from 0 to 15."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETBACKGROUND <color>"}<br/>{"SETBG"}</td>
+                    <td>{"SETBG \"midnightblue"}</td>
+                    <td>{"Paint the canvas background. It stays when the drawing is cleaned or saved as an image."}</td>
+                </tr>
+                <tr>
+                    <td>{"HSV <hue> <saturation> <value>"}</td>
+                    <td>{"COLOR HSV REPCOUNT * 10 100 100"}</td>
+                    <td>{"Output a color from hue in degrees, saturation and value from 0 to 100."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETPALETTE <index> <color>"}</td>
+                    <td>{"SETPALETTE 8 \"navy"}</td>
+                    <td>{"Change the color behind a palette index from 0 to 15."}</td>
                 </tr>
                 <tr>
                     <td>{"REPEAT <number> [ <code> ] "}</td>
                     <td>{"REPEAT 2 [ FD 50 RT 30 ]"}</td>
                     <td>{"<code> gets repeated <number> of times."}</td>
                 </tr>
+                <tr>
+                    <td>{"ARC <angle> <radius>"}<br/>{"CIRCLE <radius>"}</td>
+                    <td>{"ARC 90 50"}</td>
+                    <td>{"Draw an arc centred on the turtle, starting at its heading and going clockwise. Turtle does not move."}</td>
+                </tr>
+                <tr>
+                    <td>{"TELL <turtle or list>"}</td>
+                    <td>{"TELL [1 2 3]"}</td>
+                    <td>{"Make the listed turtles active. Turtle commands move all of them, in the order they were listed. New turtles start at home."}</td>
+                </tr>
+                <tr>
+                    <td>{"ASK <turtle or list> [ <commands> ]"}</td>
+                    <td>{"ASK 2 [ FD 50 ]"}</td>
+                    <td>{"Run the commands with only the given turtles active, then go back to the turtles picked by TELL."}</td>
+                </tr>
+                <tr>
+                    <td>{"WHO"}</td>
+                    <td>{"PRINT WHO"}</td>
+                    <td>{"Output the active turtle, or a list when several turtles are active. Turtle 0 is active at start."}</td>
+                </tr>
+                <tr>
+                    <td>{"FILLED <color> [ <commands> ]"}</td>
+                    <td>{"FILLED #ffcc00 [ REPEAT 4 [ FD 50 RT 90 ] ]"}</td>
+                    <td>{"Run the commands, then fill the shape traced by each turtle with <color>."}</td>
+                </tr>
+                <tr>
+                    <td>{"FILL"}</td>
+                    <td>{"PU FD 10 FILL"}</td>
+                    <td>{"Flood-fill the area under the turtle with the pen color, up to the surrounding lines."}</td>
+                </tr>
+                <tr>
+                    <td>{"LABEL <word or list>"}</td>
+                    <td>{"RT 90 LABEL [Hello World]"}</td>
+                    <td>{"Write text at the turtle position in the pen color. Text follows the heading, it is horizontal when heading is 90."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETLABELHEIGHT <number>"}<br/>{"SETFONT <word or list>"}</td>
+                    <td>{"SETFONT [Times New Roman]"}</td>
+                    <td>{"Set the text height in pixels and the font used by LABEL."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETPOS [ <x> <y> ]"}<br/>{"SETXY <x> <y>"}</td>
+                    <td>{"SETXY 50 100"}</td>
+                    <td>{"Move the turtle to the position. [0 0] is the centre and y grows upwards. Draws a line when the pen is down. SETX and SETY change only one coordinate."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETHEADING <degrees>"}<br/>{"SETH"}</td>
+                    <td>{"SETH 90"}</td>
+                    <td>{"Turn the turtle to face the heading. 0 is up, 90 is right."}</td>
+                </tr>
+                <tr>
+                    <td>{"HOME"}</td>
+                    <td>{"HOME"}</td>
+                    <td>{"Move the turtle back to the centre, facing up."}</td>
+                </tr>
+                <tr>
+                    <td>{"CLEAN"}<br/>{"CLEARSCREEN"}<br/>{"CS"}</td>
+                    <td>{"CS"}</td>
+                    <td>{"CLEAN erases the drawing. CLEARSCREEN also sends every turtle HOME without drawing."}</td>
+                </tr>
+                <tr>
+                    <td>{"XCOR"}<br/>{"YCOR"}<br/>{"POS"}<br/>{"HEADING"}</td>
+                    <td>{"MAKE \"start POS"}</td>
+                    <td>{"Output the turtle position or heading."}</td>
+                </tr>
+                <tr>
+                    <td>{"TOWARDS [ <x> <y> ]"}<br/>{"DISTANCEXY <x> <y>"}</td>
+                    <td>{"SETH TOWARDS [0 0]"}</td>
+                    <td>{"Output the heading that faces the point, or the distance to the point."}</td>
+                </tr>
+                <tr>
+                    <td>{"PENCOLOR"}<br/>{"PENSIZE"}</td>
+                    <td>{"SETPENSIZE PENSIZE * 2"}</td>
+                    <td>{"Output the current pen color or pen size."}</td>
+                </tr>
+                <tr>
+                    <td>{"SHOWTURTLE"}<br/>{"HIDETURTLE"}</td>
+                    <td>{"HT REPEAT 4 [ FD 50 RT 90 ] ST"}</td>
+                    <td>{"Show or hide the turtle. Short forms: ST, HT."}</td>
+                </tr>
+                <tr>
+                    <td>{"PUSHTURTLE"}<br/>{"POPTURTLE"}</td>
+                    <td>{"PUSHTURTLE LT 30 FD 20 POPTURTLE RT 30 FD 20"}</td>
+                    <td>{"Save the turtle's position, heading and pen, and later jump back to the last saved state without drawing. Also SAVESTATE and RESTORESTATE."}</td>
+                </tr>
+                <tr>
+                    <td>{"STAMP"}</td>
+                    <td>{"REPEAT 6 [ STAMP PU FD 30 PD ]"}</td>
+                    <td>{"Draw the turtle shape onto the drawing, at the turtle's position and heading, in the pen color."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETSHAPE <name or path>"}</td>
+                    <td>{"SETSHAPE \"triangle"}<br/>{"SETSHAPE [M 0 -10 L 6 6 L -6 6 Z]"}</td>
+                    <td>{"Draw the turtle as a turtle, a triangle or SVG path data. Custom paths face up and turn around their origin."}</td>
+                </tr>
+                <tr>
+                    <td>{"WRAP"}<br/>{"FENCE"}<br/>{"WINDOW"}</td>
+                    <td>{"WRAP FD 500"}</td>
+                    <td>{"Choose what happens at the canvas edge: come back from the opposite edge, stop with an error, or keep going off-screen. WINDOW is the default."}</td>
+                </tr>
+                <tr>
+                    <td>{"PENUP"}<br/>{"PU"}</td>
+                    <td>{"PU FD 20 PD"}</td>
+                    <td>{"Lift the pen. Turtle moves without drawing until PENDOWN."}</td>
+                </tr>
+                <tr>
+                    <td>{"PENDOWN"}<br/>{"PD"}</td>
+                    <td>{"PENDOWN"}</td>
+                    <td>{"Put the pen down, turtle draws again. PENDOWNP outputs true if the pen is down."}</td>
+                </tr>
+                <tr>
+                    <td>{"PENPAINT"}<br/>{"PENERASE"}<br/>{"PENREVERSE"}</td>
+                    <td>{"PENERASE BK 50"}</td>
+                    <td>{"Put the pen down to draw normally, to erase lines, or to invert the pixels under the line, so drawing it again removes it. Short forms: PPT, PE, PX."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETPENSIZE <number>"}</td>
+                    <td>{"SETPENSIZE 4"}</td>
+                    <td>{"Set the width of lines in pixels."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETLINECAP <word>"}<br/>{"SETLINEJOIN <word>"}</td>
+                    <td>{"SETLINECAP \"round"}</td>
+                    <td>{"Set how line ends look (butt, round, square) and how lines meet (miter, round, bevel)."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETDASH [ <lengths> ]"}</td>
+                    <td>{"SETDASH [5 3]"}</td>
+                    <td>{"Draw dashed lines with alternating dash and gap lengths. SETDASH [] draws solid lines again."}</td>
+                </tr>
+                <tr>
+                    <td>{"MAKE \"<name> <value>"}<br/>{":<name>"}</td>
+                    <td>{"MAKE \"size 50 FD :size"}</td>
+                    <td>{"Assign <value> to variable <name>. Read the variable with :<name>."}</td>
+                </tr>
+                <tr>
+                    <td>{"\"<word>"}<br/>{"[ <items> ]"}</td>
+                    <td>{"MAKE \"sides [ 50 80 110 ]"}</td>
+                    <td>{"Words and lists are values. Use FIRST, BUTFIRST (BF), LAST, BUTLAST (BL), ITEM, FPUT, LPUT, COUNT, EMPTYP, SENTENCE (SE), WORD and LIST to work with them."}</td>
+                </tr>
+                <tr>
+                    <td>{"+ - * / ( )"}</td>
+                    <td>{"FD (10 + 5) * 2"}</td>
+                    <td>{"Arithmetic can be used wherever a <number> is expected."}</td>
+                </tr>
+                <tr>
+                    <td>{"REPCOUNT"}</td>
+                    <td>{"REPEAT 36 [ FD REPCOUNT RT 20 ]"}</td>
+                    <td>{"Current iteration of the innermost REPEAT or FOREVER, starting from 1."}</td>
+                </tr>
+                <tr>
+                    <td>{"WHILE [ <condition> ] [ <code> ]"}<br/>{"UNTIL [ <condition> ] [ <code> ]"}</td>
+                    <td>{"WHILE [ :size < 100 ] [ FD :size MAKE \"size :size + 10 ]"}</td>
+                    <td>{"Repeat <code> while (or until) <condition> is true."}</td>
+                </tr>
+                <tr>
+                    <td>{"FOREVER [ <code> ]"}</td>
+                    <td>{"FOREVER [ FD 10 RT 10 ]"}</td>
+                    <td>{"Repeat <code> until stopped. Like every loop, it gives up after 100000 iterations."}</td>
+                </tr>
+                <tr>
+                    <td>{"FOR [ <name> <start> <end> <step> ] [ <code> ]"}</td>
+                    <td>{"FOR [ i 10 100 10 ] [ FD :i RT 90 ]"}</td>
+                    <td>{"Repeat <code> while :<name> goes from <start> to <end>. <step> is optional."}</td>
+                </tr>
+                <tr>
+                    <td>{"IF <condition> [ <code> ]"}<br/>{"IFELSE <condition> [ <code> ] [ <code> ]"}</td>
+                    <td>{"IF :size > 50 [ FD :size ]"}</td>
+                    <td>{"Run <code> only when <condition> is true. Compare with = <> < > <= >= and combine with AND, OR, NOT."}</td>
+                </tr>
+                <tr>
+                    <td>{"SIN COS TAN ARCTAN SQRT POWER EXP LN ABS INT ROUND REMAINDER MODULO PI"}</td>
+                    <td>{"FD 100 * SIN 30"}</td>
+                    <td>{"Math functions. Angles are in degrees."}</td>
+                </tr>
+                <tr>
+                    <td>{"RANDOM <number>"}<br/>{"RERANDOM <seed>"}</td>
+                    <td>{"RERANDOM 42 RT RANDOM 360"}</td>
+                    <td>{"Random whole number from 0 to <number> - 1. RERANDOM makes the sequence repeat on every run."}</td>
+                </tr>
+                <tr>
+                    <td>{"PRINT <value>"}<br/>{"SHOW <value>"}<br/>{"TYPE <value>"}</td>
+                    <td>{"PRINT :size"}</td>
+                    <td>{"Write <value> to the console output. SHOW keeps list brackets, TYPE does not start a new line."}</td>
+                </tr>
+                <tr>
+                    <td>{"; <comment>"}<br/>{"~"}</td>
+                    <td>{"FD 10 ; step ahead"}</td>
+                    <td>{"Everything after ; until the end of the line is ignored. ~ at the end of a line continues the instruction on the next line."}</td>
+                </tr>
+                <tr>
+                    <td>{"TO <name> :<param> ... <code> END"}</td>
+                    <td>{"TO SQUARE :size REPEAT 4 [ FD :size RT 90 ] END"}</td>
+                    <td>{"Define procedure <name> with parameters. Call it by name, e.g. SQUARE 50."}</td>
+                </tr>
+                <tr>
+                    <td>{"STOP"}<br/>{"OUTPUT <value>"}<br/>{"OP <value>"}</td>
+                    <td>{"TO double :x OUTPUT :x * 2 END"}</td>
+                    <td>{"End the procedure early. OUTPUT also gives <value> back to the caller, e.g. FD double 20."}</td>
+                </tr>
+                <tr>
+                    <td>{"SETRECURSIONDEPTH <number>"}</td>
+                    <td>{"SETRECURSIONDEPTH 500"}</td>
+                    <td>{"How many procedure calls can be nested before the script stops with an error. Default is 256, highest is 512."}</td>
+                </tr>
             </tbody>
         </table>
         </>